@@ -0,0 +1,245 @@
+//! Keeping prompter windows out of screen recordings and screen shares.
+//!
+//! Windows uses `SetWindowDisplayAffinity(WDA_EXCLUDEFROMCAPTURE)`, which
+//! needs Windows 10 2004 or later; macOS uses the window's sharing type.
+//! Other platforms have no equivalent, so the status says so instead of
+//! pretending. Exclusion is on for every window unless turned off in the
+//! settings, and a failure is broadcast as a warning event.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+
+use crate::config::{Config, Settings};
+
+pub const WARNING_EVENT: &str = "capture://warning";
+/// Labels of the windows this app creates.
+pub const KNOWN_WINDOWS: [&str; 2] = ["main", crate::output::OUTPUT_LABEL];
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+#[cfg_attr(not(any(target_os = "windows", target_os = "macos")), allow(dead_code))]
+pub enum CaptureStatus {
+    /// The window is excluded from capture.
+    Applied,
+    /// Exclusion is supported but turned off for this window.
+    Disabled,
+    /// The OS rejected the request.
+    Failed { code: i64, message: String },
+    /// This platform cannot exclude windows from capture.
+    Unsupported,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowCapture {
+    pub label: String,
+    pub enabled: bool,
+    pub status: CaptureStatus,
+}
+
+/// Last known status per window label.
+#[derive(Default)]
+pub struct CaptureState {
+    windows: Mutex<BTreeMap<String, WindowCapture>>,
+}
+
+impl CaptureState {
+    pub fn list(&self) -> Vec<WindowCapture> {
+        self.windows
+            .lock()
+            .map(|windows| windows.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Excludes the window `label` through `exclude` if the settings say so
+    /// and records the outcome.
+    fn apply_with(
+        &self,
+        settings: &Settings,
+        label: &str,
+        exclude: impl FnOnce(bool) -> CaptureStatus,
+    ) -> WindowCapture {
+        let enabled = is_enabled(settings, label);
+        let capture = WindowCapture {
+            label: label.to_string(),
+            enabled,
+            status: exclude(enabled),
+        };
+        if let Ok(mut windows) = self.windows.lock() {
+            windows.insert(capture.label.clone(), capture.clone());
+        }
+        capture
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn set_excluded(window: &WebviewWindow, excluded: bool) -> CaptureStatus {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_NONE,
+    };
+
+    let hwnd = match window.window_handle().map(|handle| handle.as_raw()) {
+        Ok(RawWindowHandle::Win32(win)) => HWND(win.hwnd.get() as _),
+        Ok(_) => return CaptureStatus::Unsupported,
+        Err(err) => {
+            return CaptureStatus::Failed {
+                code: 0,
+                message: err.to_string(),
+            }
+        }
+    };
+    let affinity = if excluded {
+        WDA_EXCLUDEFROMCAPTURE
+    } else {
+        WDA_NONE
+    };
+    match unsafe { SetWindowDisplayAffinity(hwnd, affinity) } {
+        Ok(()) if excluded => CaptureStatus::Applied,
+        Ok(()) => CaptureStatus::Disabled,
+        Err(err) => CaptureStatus::Failed {
+            code: err.code().0 as i64,
+            message: err.message().to_string(),
+        },
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn set_excluded(window: &WebviewWindow, excluded: bool) -> CaptureStatus {
+    match window.set_content_protected(excluded) {
+        Ok(()) if excluded => CaptureStatus::Applied,
+        Ok(()) => CaptureStatus::Disabled,
+        Err(err) => CaptureStatus::Failed {
+            code: 0,
+            message: err.to_string(),
+        },
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn set_excluded(_window: &WebviewWindow, _excluded: bool) -> CaptureStatus {
+    CaptureStatus::Unsupported
+}
+
+fn is_enabled(settings: &Settings, label: &str) -> bool {
+    settings
+        .capture_exclusion
+        .get(label)
+        .copied()
+        .unwrap_or(true)
+}
+
+/// Applies the configured exclusion to `window` and records the outcome.
+pub fn apply(app: &AppHandle, window: &WebviewWindow) -> WindowCapture {
+    let capture = app.state::<CaptureState>().apply_with(
+        &app.state::<Config>().get(),
+        window.label(),
+        |excluded| set_excluded(window, excluded),
+    );
+    if let CaptureStatus::Failed { .. } = capture.status {
+        let _ = app.emit(WARNING_EVENT, &capture);
+    }
+    capture
+}
+
+/// Whether exclusion is turned on for every known window.
+pub fn all_enabled(app: &AppHandle) -> bool {
+    let settings = app.state::<Config>().get();
+    KNOWN_WINDOWS
+        .iter()
+        .all(|label| is_enabled(&settings, label))
+}
+
+/// Saves the exclusion setting for each of `labels` and applies it to the
+/// windows that are open.
+pub fn set_enabled(app: &AppHandle, labels: &[&str], enabled: bool) -> Result<(), String> {
+    app.state::<Config>().modify(|settings| {
+        for label in labels {
+            settings
+                .capture_exclusion
+                .insert(label.to_string(), enabled);
+        }
+        Ok(())
+    })?;
+    for label in labels {
+        if let Some(window) = app.get_webview_window(label) {
+            apply(app, &window);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn capture_status(state: State<'_, CaptureState>) -> Vec<WindowCapture> {
+    state.list()
+}
+
+#[tauri::command]
+pub fn set_capture_exclusion(
+    app: AppHandle,
+    label: String,
+    enabled: bool,
+) -> Result<Vec<WindowCapture>, String> {
+    set_enabled(&app, &[&label], enabled)?;
+    Ok(app.state::<CaptureState>().list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exclusion_is_on_unless_turned_off() {
+        let mut settings = Settings::default();
+        settings.capture_exclusion.insert("talent".into(), false);
+        assert!(is_enabled(&settings, "main"));
+        assert!(!is_enabled(&settings, "talent"));
+    }
+
+    #[test]
+    fn applies_the_setting_and_keeps_the_latest_outcome() {
+        let mut settings = Settings::default();
+        settings.capture_exclusion.insert("talent".into(), false);
+        let state = CaptureState::default();
+
+        let main = state.apply_with(&settings, "main", |excluded| {
+            assert!(excluded);
+            CaptureStatus::Applied
+        });
+        assert!(main.enabled);
+        let talent = state.apply_with(&settings, "talent", |excluded| {
+            assert!(!excluded);
+            CaptureStatus::Disabled
+        });
+        assert!(!talent.enabled);
+        assert_eq!(state.list(), [main, talent.clone()]);
+
+        let failed = CaptureStatus::Failed {
+            code: 5,
+            message: "Access is denied.".into(),
+        };
+        let main = state.apply_with(&settings, "main", |_| failed.clone());
+        assert_eq!(main.status, failed);
+        assert_eq!(state.list(), [main, talent]);
+    }
+
+    #[test]
+    fn failures_carry_the_os_error_code() {
+        let failed = CaptureStatus::Failed {
+            code: -2147024809,
+            message: "The parameter is incorrect.".into(),
+        };
+        assert_eq!(
+            serde_json::to_value(failed).unwrap(),
+            serde_json::json!({
+                "status": "failed",
+                "code": -2147024809,
+                "message": "The parameter is incorrect."
+            })
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Command-line client for a running Flash Prompter, for scripting
+//! recording sessions:
+//!
+//! ```text
+//! flash-prompter play | pause | toggle | stop
+//! flash-prompter seek <0..1>
+//! flash-prompter speed <words per minute>
+//! flash-prompter load <file>
+//! flash-prompter status [--json]
+//! ```
+//!
+//! Each subcommand sends one request over the [`ipc`](crate::ipc) socket and
+//! prints the resulting playback status. Anything else on the command line
+//! starts the app as usual. Exit codes: 0 on success, 1 when the app is not
+//! running or refused the command, 2 on bad usage.
+
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::ipc::{self, Command};
+
+const USAGE: &str = "\
+usage: flash-prompter <command> [--json]
+
+commands:
+  play | pause | toggle | stop
+  seek <0..1>          jump to a position in the script
+  speed <wpm>          set the speed in words per minute
+  load <file>          open a script file
+  status               print the playback status";
+
+const SUBCOMMANDS: [&str; 9] = [
+    "play", "pause", "toggle", "stop", "seek", "speed", "load", "status", "help",
+];
+
+/// Parses a subcommand line, without the program name.
+fn parse(args: &[String], cwd: &Path) -> Result<Command, String> {
+    let (name, rest) = args.split_first().ok_or(USAGE)?;
+    let number = |what: &str| -> Result<f64, String> {
+        match rest {
+            [value] => value
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| format!("{name}: {value} is not a number")),
+            _ => Err(format!("usage: flash-prompter {name} <{what}>")),
+        }
+    };
+    let command = match name.as_str() {
+        "play" => Command::Play,
+        "pause" => Command::Pause,
+        "toggle" => Command::Toggle,
+        "stop" => Command::Stop,
+        "status" => Command::Status,
+        "seek" => Command::Seek {
+            progress: number("0..1")?,
+        },
+        "speed" => Command::Speed {
+            rate: number("wpm")?,
+        },
+        "load" => match rest {
+            [path] => Command::Load {
+                path: cwd.join(path),
+            },
+            _ => return Err("usage: flash-prompter load <file>".into()),
+        },
+        _ => return Err(USAGE.into()),
+    };
+    if !matches!(
+        command,
+        Command::Seek { .. } | Command::Speed { .. } | Command::Load { .. }
+    ) && !rest.is_empty()
+    {
+        return Err(format!("{name} takes no arguments"));
+    }
+    Ok(command)
+}
+
+fn describe(status: &Value) -> String {
+    let seconds = |key: &str| {
+        let total = status[key].as_f64().unwrap_or(0.0).max(0.0).round() as u64;
+        format!("{}:{:02}", total / 60, total % 60)
+    };
+    let pace = if status["timed"].as_bool().unwrap_or(false) {
+        "timed".to_string()
+    } else {
+        format!("{:.0} wpm", status["rate"].as_f64().unwrap_or(0.0))
+    };
+    format!(
+        "{} {:.0}% {}/{} {pace}",
+        status["state"].as_str().unwrap_or("unknown"),
+        status["progress"].as_f64().unwrap_or(0.0) * 100.0,
+        seconds("elapsedSeconds"),
+        seconds("durationSeconds"),
+    )
+}
+
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // The release binary is a GUI app without a console of its own.
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_console() {}
+
+/// Runs the client when the command line starts with a subcommand. Returns
+/// the exit code, or `None` when the app should start normally.
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args
+        .first()
+        .is_some_and(|arg| SUBCOMMANDS.contains(&arg.as_str()))
+    {
+        return None;
+    }
+    attach_console();
+    let json = args.iter().any(|arg| arg == "--json");
+    let args: Vec<String> = args.into_iter().filter(|arg| arg != "--json").collect();
+    if args[0] == "help" {
+        println!("{USAGE}");
+        return Some(0);
+    }
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let command = match parse(&args, &cwd) {
+        Ok(command) => command,
+        Err(usage) => {
+            eprintln!("{usage}");
+            return Some(2);
+        }
+    };
+    let response = match ipc::socket_name().and_then(|name| ipc::request(name, command)) {
+        Ok(response) => response,
+        Err(err) => {
+            eprintln!("Flash Prompter is not running ({err})");
+            return Some(1);
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string(&response).unwrap_or_default());
+    } else if let Some(error) = &response.error {
+        eprintln!("{error}");
+    } else if let Some(status) = &response.status {
+        println!("{}", describe(status));
+    }
+    Some(if response.ok { 0 } else { 1 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_subcommands() {
+        let cwd = Path::new("scripts");
+        assert_eq!(
+            parse(&args("seek 0.5"), cwd),
+            Ok(Command::Seek { progress: 0.5 })
+        );
+        assert_eq!(
+            parse(&args("speed 120"), cwd),
+            Ok(Command::Speed { rate: 120.0 })
+        );
+        assert_eq!(
+            parse(&args("load intro.md"), cwd),
+            Ok(Command::Load {
+                path: cwd.join("intro.md")
+            })
+        );
+        assert!(parse(&args("seek"), cwd).is_err());
+        assert!(parse(&args("speed fast"), cwd).is_err());
+        assert!(parse(&args("play now"), cwd).is_err());
+    }
+
+    #[test]
+    fn describes_the_status() {
+        let status = serde_json::json!({
+            "state": "playing",
+            "progress": 0.25,
+            "elapsedSeconds": 65.0,
+            "durationSeconds": 260.0,
+            "rate": 120.0
+        });
+        assert_eq!(describe(&status), "playing 25% 1:05/4:20 120 wpm");
+    }
+}
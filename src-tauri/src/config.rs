@@ -0,0 +1,907 @@
+//! User settings persisted as `settings.json` in the app config dir.
+//!
+//! The file carries a `version` field. Older layouts are upgraded by the
+//! `MIGRATIONS` chain on load, values are clamped to their valid ranges and
+//! fields that are missing or malformed fall back to their defaults one by
+//! one, so a hand-written partial file is fine. A file that is not JSON at all
+//! is moved to `settings.json.bak` rather than overwritten. On first launch
+//! the settings are seeded from `settings.defaults.json` next to the
+//! executable when present, which lets shared machines be pre-provisioned.
+//!
+//! Named profiles live in the same file. The top-level tuning values are the
+//! ones in effect; while a profile is active, changes to them are written
+//! back into it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::deep_link::LinkAllowlist;
+use crate::placement::Placement;
+
+pub const SETTINGS_EVENT: &str = "settings://changed";
+pub const SETTINGS_VERSION: u64 = 2;
+pub const DEFAULTS_FILE: &str = "settings.defaults.json";
+const DEFAULT_PROFILE: &str = "默认";
+
+/// Values a profile captures.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Tuning {
+    pub words_per_minute: u32,
+    pub font_size: u32,
+    pub line_height: u32,
+    pub text_color: String,
+    pub background_color: String,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            words_per_minute: 60,
+            font_size: 34,
+            line_height: 48,
+            text_color: "#f5f5f5".into(),
+            background_color: "#0f0f0f".into(),
+        }
+    }
+}
+
+impl Tuning {
+    fn clamped(mut self) -> Self {
+        let defaults = Tuning::default();
+        self.words_per_minute = self.words_per_minute.clamp(20, 240);
+        self.font_size = self.font_size.clamp(20, 64);
+        self.line_height = self.line_height.clamp(28, 96);
+        if !is_hex_color(&self.text_color) {
+            self.text_color = defaults.text_color;
+        }
+        if !is_hex_color(&self.background_color) {
+            self.background_color = defaults.background_color;
+        }
+        self
+    }
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Window placement in logical pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Flips applied to the output window for beam-splitter glass.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Mirror {
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+/// What a launch at login does differently from a manual one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutostartLaunch {
+    /// Start with only the tray icon instead of showing the main window.
+    pub start_hidden: bool,
+    /// Open the most recently edited script from the library.
+    pub restore_last_script: bool,
+    pub skip_update_check: bool,
+}
+
+impl Default for AutostartLaunch {
+    fn default() -> Self {
+        Self {
+            start_hidden: true,
+            restore_last_script: false,
+            skip_update_check: true,
+        }
+    }
+}
+
+/// Click-through overlay for sitting the prompter on top of a video call.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Overlay {
+    pub enabled: bool,
+    /// Opacity of the prompter background while the overlay is on.
+    pub opacity: f64,
+}
+
+impl Default for Overlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            opacity: 0.6,
+        }
+    }
+}
+
+impl Overlay {
+    fn clamped(mut self) -> Self {
+        self.opacity = if self.opacity.is_finite() {
+            self.opacity.clamp(0.1, 1.0)
+        } else {
+            Overlay::default().opacity
+        };
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Profile {
+    pub name: String,
+    #[serde(flatten)]
+    pub tuning: Tuning,
+    pub window: Option<WindowGeometry>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    #[serde(flatten)]
+    pub tuning: Tuning,
+    pub auto_start: bool,
+    pub autostart_launch: AutostartLaunch,
+    pub placement: Placement,
+    /// Key of the monitor the prompter is pinned to.
+    pub target_monitor: Option<String>,
+    /// Key of the monitor the talent window opens on.
+    pub output_monitor: Option<String>,
+    pub mirror: Mirror,
+    pub overlay: Overlay,
+    /// Per-window capture exclusion by window label; missing means on.
+    pub capture_exclusion: BTreeMap<String, bool>,
+    /// Actions `flashprompter://` links may trigger.
+    pub link_allowlist: LinkAllowlist,
+    pub active_profile: Option<String>,
+    pub profiles: Vec<Profile>,
+}
+
+impl Settings {
+    fn clamped(mut self) -> Self {
+        self.tuning = self.tuning.clamped();
+        self.overlay = self.overlay.clamped();
+        let mut profiles: Vec<Profile> = Vec::new();
+        for mut profile in self.profiles {
+            profile.name = profile.name.trim().to_string();
+            if profile.name.is_empty() || profiles.iter().any(|p| p.name == profile.name) {
+                continue;
+            }
+            profile.tuning = profile.tuning.clamped();
+            profiles.push(profile);
+        }
+        self.profiles = profiles;
+        if let Some(active) = &self.active_profile {
+            if !self.profiles.iter().any(|p| &p.name == active) {
+                self.active_profile = None;
+            }
+        }
+        self
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    fn activate(&mut self, name: &str) -> Result<(), String> {
+        let profile = self
+            .profile(name)
+            .cloned()
+            .ok_or_else(|| format!("no profile named {name}"))?;
+        self.tuning = profile.tuning;
+        self.active_profile = Some(profile.name);
+        Ok(())
+    }
+
+    fn upsert(&mut self, profile: Profile) {
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+}
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades a version `n` file to version `n + 1`.
+const MIGRATIONS: [Migration; SETTINGS_VERSION as usize] = [from_unversioned, into_profiles];
+
+/// Version 0 is the flat shape the webview kept in `localStorage`, which is
+/// also what people tend to write by hand. Numbers may have been stored as
+/// strings there.
+fn from_unversioned(map: &mut Map<String, Value>) {
+    for value in map.values_mut() {
+        if let Some(number) = value
+            .as_str()
+            .and_then(|raw| raw.trim().parse::<f64>().ok())
+        {
+            *value = Value::from(number);
+        }
+    }
+}
+
+/// Version 2 introduced profiles; the existing values become the first one.
+fn into_profiles(map: &mut Map<String, Value>) {
+    let mut profile: Map<String, Value> = map
+        .iter()
+        .filter(|(key, _)| *key != "autoStart")
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    profile.insert("name".into(), Value::from(DEFAULT_PROFILE));
+    map.insert(
+        "profiles".into(),
+        Value::Array(vec![Value::Object(profile)]),
+    );
+    map.insert("activeProfile".into(), Value::from(DEFAULT_PROFILE));
+}
+
+/// Runs the migrations a raw settings document needs, without validating it.
+fn upgrade(raw: Value) -> Map<String, Value> {
+    let mut map = match raw {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+    let version = map.remove("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(&mut map);
+    }
+    map
+}
+
+/// Upgrades a raw settings document to the current version and validates it.
+pub fn migrate(raw: Value) -> Settings {
+    from_fields(upgrade(raw))
+}
+
+fn from_fields(mut map: Map<String, Value>) -> Settings {
+    if let Some(Value::Array(profiles)) = map.get_mut("profiles") {
+        for profile in profiles.iter_mut() {
+            if let Value::Object(fields) = profile {
+                *profile = serde_json::to_value(lenient::<Profile>(std::mem::take(fields)))
+                    .unwrap_or_default();
+            }
+        }
+    }
+    lenient::<Settings>(map).clamped()
+}
+
+/// Deserializes `map` field by field on top of `T::default()`, dropping the
+/// fields that would not parse. Numbers are rounded, since every numeric
+/// setting is an integer.
+fn lenient<T: Default + Serialize + DeserializeOwned>(map: Map<String, Value>) -> T {
+    let mut merged = match serde_json::to_value(T::default()) {
+        Ok(Value::Object(merged)) => merged,
+        _ => Map::new(),
+    };
+    for (key, value) in map {
+        let Some(default) = merged.get(&key) else {
+            continue;
+        };
+        let value = match (default, value) {
+            (Value::Number(_), Value::Number(number)) => number
+                .as_f64()
+                .map(|n| Value::from(n.round().max(0.0) as u64))
+                .unwrap_or(Value::Null),
+            (_, value) => value,
+        };
+        let previous = merged.insert(key.clone(), value);
+        if serde_json::from_value::<T>(Value::Object(merged.clone())).is_err() {
+            if let Some(previous) = previous {
+                merged.insert(key, previous);
+            }
+        }
+    }
+    serde_json::from_value(Value::Object(merged)).unwrap_or_default()
+}
+
+/// File written by profile export.
+#[derive(Serialize, Deserialize)]
+struct ProfileBundle {
+    version: u64,
+    profiles: Vec<Value>,
+}
+
+type Listener = Box<dyn Fn(&Settings) + Send + Sync>;
+
+/// Settings owned by the backend. Listeners run after every change.
+#[derive(Clone)]
+pub struct Config {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    path: PathBuf,
+    settings: Mutex<Settings>,
+    listeners: Mutex<Vec<Listener>>,
+    /// Why the stored settings could not be used, if they could not.
+    load_warning: Option<String>,
+}
+
+/// What was found at the settings path.
+enum Stored {
+    Settings(Value),
+    Missing,
+    /// Unreadable; the file is left in place and not overwritten.
+    Unusable(String),
+    /// Not JSON; the file was moved aside, so a new one can be written.
+    MovedAside(String),
+}
+
+fn read_stored(path: &Path) -> Stored {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Stored::Missing,
+        Err(err) => {
+            return Stored::Unusable(format!("{} could not be read: {err}", path.display()))
+        }
+    };
+    let err = match serde_json::from_str(&raw) {
+        Ok(value) => return Stored::Settings(value),
+        Err(err) => err,
+    };
+    let backup = path.with_extension("json.bak");
+    match fs::rename(path, &backup) {
+        Ok(()) => Stored::MovedAside(format!(
+            "{} is not valid JSON ({err}). It was moved to {} and the default settings are used.",
+            path.display(),
+            backup.display()
+        )),
+        Err(move_err) => Stored::Unusable(format!(
+            "{} is not valid JSON ({err}) and could not be moved aside: {move_err}",
+            path.display()
+        )),
+    }
+}
+
+impl Config {
+    /// Loads `path`, seeding it from `defaults` if it does not exist yet.
+    pub fn load(path: PathBuf, defaults: Option<&Path>) -> Self {
+        let (stored, seeded, load_warning) = match read_stored(&path) {
+            Stored::Settings(value) => (Some(value), false, None),
+            Stored::Missing => (None, true, None),
+            Stored::Unusable(warning) => (None, false, Some(warning)),
+            Stored::MovedAside(warning) => (None, true, Some(warning)),
+        };
+        let settings = stored
+            .or_else(|| defaults.and_then(read_json))
+            .map(migrate)
+            .unwrap_or_default();
+        let config = Self {
+            inner: Arc::new(Inner {
+                path,
+                settings: Mutex::new(settings.clone()),
+                listeners: Mutex::new(Vec::new()),
+                load_warning,
+            }),
+        };
+        if seeded {
+            let _ = config.store(&settings);
+        }
+        config
+    }
+
+    /// Set when the settings file existed but could not be used, for the
+    /// app to tell the user.
+    pub fn load_warning(&self) -> Option<&str> {
+        self.inner.load_warning.as_deref()
+    }
+
+    pub fn subscribe(&self, listener: impl Fn(&Settings) + Send + Sync + 'static) {
+        if let Ok(mut listeners) = self.inner.listeners.lock() {
+            listeners.push(Box::new(listener));
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.inner
+            .settings
+            .lock()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    /// Applies the fields present in `patch` on top of the current settings.
+    pub fn update(&self, patch: Value) -> Result<Settings, String> {
+        let Value::Object(patch) = patch else {
+            return Err("settings patch must be an object".into());
+        };
+        self.modify(|settings| {
+            let mut fields = match serde_json::to_value(&*settings) {
+                Ok(Value::Object(fields)) => fields,
+                _ => Map::new(),
+            };
+            fields.extend(patch);
+            *settings = from_fields(fields);
+            if let Some(active) = settings.active_profile.clone() {
+                let tuning = settings.tuning.clone();
+                if let Some(profile) = settings.profiles.iter_mut().find(|p| p.name == active) {
+                    profile.tuning = tuning;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Restores the default values. Saved profiles are kept.
+    pub fn reset(&self) -> Result<Settings, String> {
+        self.modify(|settings| {
+            *settings = Settings {
+                profiles: std::mem::take(&mut settings.profiles),
+                ..Settings::default()
+            };
+            Ok(())
+        })
+    }
+
+    /// Flips the output window horizontally, the usual beam-splitter setup.
+    pub fn toggle_mirror(&self) -> Result<Settings, String> {
+        self.modify(|settings| {
+            settings.mirror.horizontal = !settings.mirror.horizontal;
+            Ok(())
+        })
+    }
+
+    /// Turns the click-through overlay on or off.
+    pub fn toggle_overlay(&self) -> Result<Settings, String> {
+        self.modify(|settings| {
+            settings.overlay.enabled = !settings.overlay.enabled;
+            Ok(())
+        })
+    }
+
+    /// Makes `name` the active profile and loads its values.
+    pub fn switch_profile(&self, name: &str) -> Result<Settings, String> {
+        self.modify(|settings| settings.activate(name))
+    }
+
+    /// Switches to the profile after the active one, wrapping around.
+    pub fn next_profile(&self) -> Result<Settings, String> {
+        self.modify(|settings| {
+            let current = settings
+                .active_profile
+                .as_ref()
+                .and_then(|active| settings.profiles.iter().position(|p| &p.name == active));
+            let next = match current {
+                Some(index) => (index + 1) % settings.profiles.len(),
+                None => 0,
+            };
+            let name = settings
+                .profiles
+                .get(next)
+                .map(|profile| profile.name.clone())
+                .ok_or("no profiles saved")?;
+            settings.activate(&name)
+        })
+    }
+
+    /// Saves the current values as profile `name` and makes it active. A
+    /// `None` window keeps the geometry the profile already had.
+    pub fn save_profile(
+        &self,
+        name: &str,
+        window: Option<WindowGeometry>,
+    ) -> Result<Settings, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("profile name is empty".into());
+        }
+        self.modify(|settings| {
+            let window = window.or_else(|| settings.profile(name).and_then(|p| p.window));
+            settings.upsert(Profile {
+                name: name.to_string(),
+                tuning: settings.tuning.clone(),
+                window,
+            });
+            settings.active_profile = Some(name.to_string());
+            Ok(())
+        })
+    }
+
+    pub fn delete_profile(&self, name: &str) -> Result<Settings, String> {
+        self.modify(|settings| {
+            settings.profiles.retain(|profile| profile.name != name);
+            if settings.active_profile.as_deref() == Some(name) {
+                settings.active_profile = None;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn export_profiles(&self, path: &Path) -> Result<(), String> {
+        let profiles = self
+            .get()
+            .profiles
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        let bundle = ProfileBundle {
+            version: SETTINGS_VERSION,
+            profiles,
+        };
+        let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    /// Merges the profiles from an exported file, replacing same-named ones.
+    pub fn import_profiles(&self, path: &Path) -> Result<Settings, String> {
+        let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let bundle: ProfileBundle = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+        let imported = from_fields(Map::from_iter([(
+            "profiles".to_string(),
+            Value::Array(bundle.profiles),
+        )]))
+        .profiles;
+        if imported.is_empty() {
+            return Err("file contains no profiles".into());
+        }
+        self.modify(|settings| {
+            for profile in imported {
+                settings.upsert(profile);
+            }
+            Ok(())
+        })
+    }
+
+    /// Applies the settings the webview used to keep in `localStorage`. They
+    /// go through the migrations like an unversioned file, and only the
+    /// values they had are changed.
+    pub fn import_legacy(&self, legacy: Value) -> Result<Settings, String> {
+        let fields = upgrade(legacy);
+        let Value::Object(migrated) =
+            serde_json::to_value(from_fields(fields.clone())).map_err(|e| e.to_string())?
+        else {
+            return Err("settings are not an object".into());
+        };
+        let patch = migrated
+            .into_iter()
+            .filter(|(key, _)| fields.contains_key(key))
+            .collect();
+        self.update(Value::Object(patch))
+    }
+
+    /// Changes the settings under one lock, so changes made at the same time
+    /// from the UI, hotkeys and the remote cannot overwrite each other, then
+    /// saves them and tells the listeners.
+    pub fn modify(
+        &self,
+        change: impl FnOnce(&mut Settings) -> Result<(), String>,
+    ) -> Result<Settings, String> {
+        let mut current = self.inner.settings.lock().map_err(|e| e.to_string())?;
+        let mut settings = current.clone();
+        change(&mut settings)?;
+        let settings = settings.clamped();
+        self.store(&settings)?;
+        *current = settings.clone();
+        // Taken before the settings are released so listeners see changes
+        // in the order they were made.
+        let listeners = self.inner.listeners.lock();
+        drop(current);
+        if let Ok(listeners) = listeners {
+            for listener in listeners.iter() {
+                listener(&settings);
+            }
+        }
+        Ok(settings)
+    }
+
+    fn store(&self, settings: &Settings) -> Result<(), String> {
+        let path = &self.inner.path;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let mut document = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+        if let Value::Object(map) = &mut document {
+            map.insert("version".into(), Value::from(SETTINGS_VERSION));
+        }
+        let json = serde_json::to_string_pretty(&document).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, path).map_err(|e| e.to_string())
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+/// Location of the provisioning file shipped next to the executable.
+pub fn defaults_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.join(DEFAULTS_FILE))
+}
+
+#[tauri::command]
+pub fn get_settings(config: tauri::State<'_, Config>) -> Settings {
+    config.get()
+}
+
+#[tauri::command]
+pub fn update_settings(config: tauri::State<'_, Config>, patch: Value) -> Result<Settings, String> {
+    config.update(patch)
+}
+
+#[tauri::command]
+pub fn import_legacy_settings(
+    config: tauri::State<'_, Config>,
+    legacy: Value,
+) -> Result<Settings, String> {
+    config.import_legacy(legacy)
+}
+
+#[tauri::command]
+pub fn reset_settings(config: tauri::State<'_, Config>) -> Result<Settings, String> {
+    config.reset()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "flash-prompter-config-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("settings.json")
+    }
+
+    #[test]
+    fn migrates_unversioned_local_storage_shape() {
+        let settings = migrate(json!({
+            "wordsPerMinute": "90",
+            "fontSize": 40,
+            "lineHeight": 52.4,
+            "autoStart": true
+        }));
+        let tuning = Tuning {
+            words_per_minute: 90,
+            font_size: 40,
+            line_height: 52,
+            ..Tuning::default()
+        };
+        assert_eq!(settings.tuning, tuning);
+        assert!(settings.auto_start);
+        assert_eq!(settings.active_profile.as_deref(), Some(DEFAULT_PROFILE));
+        assert_eq!(settings.profiles.len(), 1);
+        assert_eq!(settings.profiles[0].tuning, tuning);
+    }
+
+    #[test]
+    fn clamps_and_ignores_invalid_fields() {
+        let settings = migrate(json!({
+            "version": SETTINGS_VERSION,
+            "wordsPerMinute": 1000,
+            "fontSize": "huge",
+            "textColor": "red",
+            "autoStart": 1,
+            "overlay": { "enabled": true, "opacity": 5 },
+            "activeProfile": "missing",
+            "profiles": [{ "name": "Slow", "wordsPerMinute": 5 }, { "fontSize": 30 }],
+            "unknown": true
+        }));
+        assert_eq!(settings.tuning.words_per_minute, 240);
+        assert_eq!(settings.tuning.font_size, Tuning::default().font_size);
+        assert_eq!(settings.tuning.text_color, Tuning::default().text_color);
+        assert!(!settings.auto_start);
+        assert!(settings.overlay.enabled);
+        assert_eq!(settings.overlay.opacity, 1.0);
+        assert_eq!(settings.active_profile, None);
+        assert_eq!(settings.profiles.len(), 1);
+        assert_eq!(settings.profiles[0].tuning.words_per_minute, 20);
+    }
+
+    #[test]
+    fn seeds_from_defaults_and_persists_updates() {
+        let path = temp_path("seed");
+        let defaults = path.with_file_name("defaults.json");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&defaults, r#"{"version": 2, "fontSize": 50}"#).unwrap();
+
+        let config = Config::load(path.clone(), Some(&defaults));
+        assert_eq!(config.get().tuning.font_size, 50);
+
+        let seen = Arc::new(Mutex::new(None));
+        let sink = seen.clone();
+        config.subscribe(move |settings| *sink.lock().unwrap() = Some(settings.clone()));
+        let updated = config.update(json!({ "lineHeight": 60 })).unwrap();
+        assert_eq!(updated.tuning.font_size, 50);
+        assert_eq!(seen.lock().unwrap().as_ref(), Some(&updated));
+
+        fs::write(&defaults, r#"{"fontSize": 20}"#).unwrap();
+        let reloaded = Config::load(path.clone(), Some(&defaults)).get();
+        assert_eq!(reloaded, updated);
+        let stored: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored["version"], json!(SETTINGS_VERSION));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn moves_a_corrupt_file_aside_instead_of_overwriting_it() {
+        let path = temp_path("corrupt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, r#"{"fontSize": 50,"#).unwrap();
+
+        let config = Config::load(path.clone(), None);
+        assert!(config.load_warning().unwrap().contains("settings.json.bak"));
+        assert_eq!(config.get(), Settings::default());
+        let backup = path.with_file_name("settings.json.bak");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), r#"{"fontSize": 50,"#);
+        assert!(Config::load(path.clone(), None).load_warning().is_none());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn imports_legacy_settings_through_the_migrations() {
+        let path = temp_path("legacy");
+        let config = Config::load(path.clone(), None);
+        config
+            .update(json!({ "placement": { "mode": "leftGutter" } }))
+            .unwrap();
+        let settings = config
+            .import_legacy(json!({ "wordsPerMinute": "90", "fontSize": 40, "autoStart": true }))
+            .unwrap();
+        assert_eq!(settings.tuning.words_per_minute, 90);
+        assert_eq!(settings.tuning.font_size, 40);
+        assert!(settings.auto_start);
+        // Values the legacy settings did not have are kept.
+        assert_ne!(settings.placement, Placement::default());
+        assert_eq!(settings.active_profile.as_deref(), Some(DEFAULT_PROFILE));
+        assert_eq!(settings.profiles[0].tuning.words_per_minute, 90);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn concurrent_changes_are_not_lost() {
+        let path = temp_path("concurrent");
+        let config = Config::load(path.clone(), None);
+        let threads: Vec<_> = (0..8)
+            .map(|thread| {
+                let config = config.clone();
+                std::thread::spawn(move || {
+                    for round in 0..10 {
+                        config
+                            .modify(|settings| {
+                                let label = format!("window-{thread}-{round}");
+                                settings.capture_exclusion.insert(label, true);
+                                Ok(())
+                            })
+                            .unwrap();
+                        config.toggle_mirror().unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        let settings = config.get();
+        assert_eq!(settings.capture_exclusion.len(), 80);
+        assert!(!settings.mirror.horizontal);
+        assert_eq!(Config::load(path.clone(), None).get(), settings);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn profiles_switch_track_edits_and_round_trip() {
+        let path = temp_path("profiles");
+        let config = Config::load(path.clone(), None);
+        config.update(json!({ "wordsPerMinute": 100 })).unwrap();
+        let window = WindowGeometry {
+            x: 10.0,
+            y: -20.0,
+            width: 420.0,
+            height: 300.0,
+        };
+        config.save_profile("Stage", Some(window)).unwrap();
+        config.save_profile("Slow reader", None).unwrap();
+        config.update(json!({ "wordsPerMinute": 40 })).unwrap();
+        config.update(json!({ "fontSize": 60 })).unwrap();
+
+        let settings = config.switch_profile("Stage").unwrap();
+        assert_eq!(settings.tuning.words_per_minute, 100);
+        assert_eq!(settings.profile("Stage").unwrap().window, Some(window));
+        let settings = config.next_profile().unwrap();
+        assert_eq!(settings.active_profile.as_deref(), Some("Slow reader"));
+        assert_eq!(settings.tuning.words_per_minute, 40);
+        assert_eq!(settings.tuning.font_size, 60);
+
+        let bundle = path.with_file_name("profiles.json");
+        config.export_profiles(&bundle).unwrap();
+        let other = Config::load(path.with_file_name("other.json"), None);
+        let imported = other.import_profiles(&bundle).unwrap();
+        assert_eq!(imported.profiles, config.get().profiles);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn profile_changes_persist_and_handle_duplicates_and_deletion() {
+        let path = temp_path("profile-edge-cases");
+        let config = Config::load(path.clone(), None);
+        let names = |settings: &Settings| -> Vec<String> {
+            settings.profiles.iter().map(|p| p.name.clone()).collect()
+        };
+        let existing = config.get().profiles.len();
+        assert_eq!(
+            config.save_profile("  ", None).unwrap_err(),
+            "profile name is empty"
+        );
+        assert!(config.switch_profile("Missing").is_err());
+
+        config.update(json!({ "wordsPerMinute": 80 })).unwrap();
+        config.save_profile(" Host ", None).unwrap();
+        config.save_profile("Guest", None).unwrap();
+        // Edits go to the active profile only.
+        let settings = config.update(json!({ "wordsPerMinute": 120 })).unwrap();
+        assert_eq!(settings.active_profile.as_deref(), Some("Guest"));
+        assert_eq!(names(&settings)[existing..], ["Host", "Guest"]);
+        // Wraps around to the first profile.
+        let settings = config.next_profile().unwrap();
+        assert_eq!(
+            settings.active_profile,
+            config.get().profiles[0].name.clone().into()
+        );
+        let settings = config.switch_profile("Host").unwrap();
+        assert_eq!(settings.tuning.words_per_minute, 80);
+        assert_eq!(Config::load(path.clone(), None).get(), settings);
+
+        // An imported profile replaces the one with the same name.
+        let bundle = path.with_file_name("bundle.json");
+        let other = Config::load(path.with_file_name("other.json"), None);
+        other.update(json!({ "wordsPerMinute": 200 })).unwrap();
+        other.save_profile("Host", None).unwrap();
+        other.export_profiles(&bundle).unwrap();
+        let settings = config.import_profiles(&bundle).unwrap();
+        assert_eq!(names(&settings).len(), existing + 2);
+        assert_eq!(
+            settings.profile("Host").unwrap().tuning.words_per_minute,
+            200
+        );
+        assert_eq!(
+            settings.profile("Guest").unwrap().tuning.words_per_minute,
+            120
+        );
+        assert_eq!(settings.active_profile.as_deref(), Some("Host"));
+
+        // Deleting the active profile leaves no profile active but keeps the
+        // values in use.
+        let settings = config.delete_profile("Host").unwrap();
+        assert_eq!(settings.active_profile, None);
+        assert_eq!(settings.tuning.words_per_minute, 80);
+        assert!(settings.profile("Host").is_none());
+        let settings = config.next_profile().unwrap();
+        assert_eq!(
+            settings.active_profile,
+            settings.profiles[0].name.clone().into()
+        );
+        assert_eq!(Config::load(path.clone(), None).get(), settings);
+
+        fs::write(&bundle, r#"{ "version": 2, "profiles": [] }"#).unwrap();
+        assert_eq!(
+            config.import_profiles(&bundle).unwrap_err(),
+            "file contains no profiles"
+        );
+        for profile in names(&config.get()) {
+            config.delete_profile(&profile).unwrap();
+        }
+        assert_eq!(config.next_profile().unwrap_err(), "no profiles saved");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::config::Config;
+use crate::markup::Document;
+use crate::playback::{Playback, PlaybackSnapshot};
+
+/// Playback actions that can be triggered from outside the UI.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum Control {
+    Play,
+    Pause,
+    Toggle,
+    Stop,
+    Seek { progress: f64 },
+    Nudge { seconds: f64 },
+    LineBack,
+    Speed { rate: f64 },
+    ToggleMirror,
+}
+
+/// Entry point shared by every external input (remote server, shortcuts, ...).
+pub trait Controller: Send + Sync + 'static {
+    fn status(&self) -> PlaybackSnapshot;
+    fn apply(&self, control: Control) -> Result<PlaybackSnapshot, String>;
+    /// Calls `listener` with every snapshot playback publishes.
+    fn subscribe(&self, listener: Box<dyn Fn(&PlaybackSnapshot) + Send + Sync>);
+}
+
+impl Controller for Playback {
+    fn status(&self) -> PlaybackSnapshot {
+        self.snapshot()
+    }
+
+    fn apply(&self, control: Control) -> Result<PlaybackSnapshot, String> {
+        Ok(match control {
+            Control::Play => self.play(),
+            Control::Pause => self.pause(),
+            Control::Toggle => self.toggle(),
+            Control::Stop => self.stop(),
+            Control::Seek { progress } => self.seek(finite(progress)?),
+            Control::Nudge { seconds } => self.nudge(finite(seconds)?),
+            Control::LineBack => self.line_back(),
+            Control::Speed { rate } => self.set_rate(finite(rate)?),
+            Control::ToggleMirror => return Err("mirroring is not a playback control".into()),
+        })
+    }
+
+    fn subscribe(&self, listener: Box<dyn Fn(&PlaybackSnapshot) + Send + Sync>) {
+        Playback::subscribe(self, listener);
+    }
+}
+
+/// Controller used by the app: playback plus the actions backed by settings.
+/// Speed changes go through the settings so they persist.
+pub struct AppControl {
+    playback: Playback,
+    config: Config,
+}
+
+impl AppControl {
+    pub fn new(playback: Playback, config: Config) -> Self {
+        Self { playback, config }
+    }
+
+    /// Replaces the script being played.
+    pub fn load(&self, text: &str) -> PlaybackSnapshot {
+        self.playback.load(text)
+    }
+
+    pub fn document(&self) -> Document {
+        self.playback.document()
+    }
+}
+
+impl Controller for AppControl {
+    fn status(&self) -> PlaybackSnapshot {
+        self.playback.snapshot()
+    }
+
+    fn apply(&self, control: Control) -> Result<PlaybackSnapshot, String> {
+        match control {
+            Control::Speed { rate } => {
+                self.config
+                    .update(json!({ "wordsPerMinute": finite(rate)? }))?;
+            }
+            Control::ToggleMirror => {
+                self.config.toggle_mirror()?;
+            }
+            control => return self.playback.apply(control),
+        }
+        Ok(self.playback.snapshot())
+    }
+
+    fn subscribe(&self, listener: Box<dyn Fn(&PlaybackSnapshot) + Send + Sync>) {
+        self.playback.subscribe(listener);
+    }
+}
+
+fn finite(value: f64) -> Result<f64, String> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(format!("invalid value: {value}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playback::SystemClock;
+    use std::sync::Arc;
+
+    #[test]
+    fn app_control_persists_speed_and_mirror() {
+        let dir =
+            std::env::temp_dir().join(format!("flash-prompter-control-{}", std::process::id()));
+        let config = Config::load(dir.join("settings.json"), None);
+        let playback = Playback::new(Arc::new(SystemClock::new()));
+        let rate_target = playback.clone();
+        config.subscribe(move |settings| {
+            rate_target.set_rate(settings.tuning.words_per_minute as f64);
+        });
+        let control = AppControl::new(playback.clone(), config.clone());
+
+        let snapshot = control.apply(Control::Speed { rate: 150.4 }).unwrap();
+        assert_eq!(snapshot.rate, 150.0);
+        assert_eq!(config.get().tuning.words_per_minute, 150);
+
+        control.apply(Control::ToggleMirror).unwrap();
+        assert!(config.get().mirror.horizontal);
+        assert!(playback.apply(Control::ToggleMirror).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
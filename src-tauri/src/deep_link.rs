@@ -0,0 +1,340 @@
+//! `flashprompter://` links from browsers, documents and shell scripts.
+//!
+//! Supported links:
+//!
+//! - `flashprompter://play`, `pause`, `toggle`, `stop`: playback controls
+//! - `flashprompter://load?script=<id>`: opens a script from the library, by
+//!   id or by title
+//! - `flashprompter://open?url=<http(s) url>`: downloads a text script
+//!
+//! Anyone can put such a link on a web page, so each action has to be on
+//! the allowlist in the settings. `open` fetches arbitrary URLs and is off
+//! until the user turns it on. Playback goes through the same `AppControl`
+//! as the UI, hotkeys and the remote.
+//!
+//! On macOS links arrive through the deep-link plugin; on Windows and Linux
+//! the OS starts the app with the link as its argument, which the
+//! single-instance plugin forwards like any other launch argument.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+use crate::config::Config;
+use crate::control::{AppControl, Control, Controller};
+use crate::launch;
+use crate::script_file::{self, OpenedFile};
+use crate::scripts::{self, ScriptLibrary};
+
+pub const SCHEME: &str = "flashprompter";
+/// Emitted with the link when it was refused or failed.
+pub const REJECTED_EVENT: &str = "deep-link://rejected";
+const MAX_DOWNLOAD: usize = 4 * 1024 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkAction {
+    Open,
+    Load,
+    Play,
+    Pause,
+    Toggle,
+    Stop,
+}
+
+/// Link actions external links may trigger.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LinkAllowlist(pub BTreeSet<LinkAction>);
+
+impl Default for LinkAllowlist {
+    fn default() -> Self {
+        Self(BTreeSet::from([
+            LinkAction::Load,
+            LinkAction::Play,
+            LinkAction::Pause,
+            LinkAction::Toggle,
+            LinkAction::Stop,
+        ]))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Link {
+    Open { url: Url },
+    Load { script: String },
+    Control(Control),
+}
+
+impl Link {
+    /// The allowlist entry covering this link, or `None` for controls that
+    /// links may not trigger. Every control is listed so that a new one is
+    /// refused until it is given an action on purpose.
+    pub fn action(&self) -> Option<LinkAction> {
+        match self {
+            Link::Open { .. } => Some(LinkAction::Open),
+            Link::Load { .. } => Some(LinkAction::Load),
+            Link::Control(control) => match control {
+                Control::Play => Some(LinkAction::Play),
+                Control::Pause => Some(LinkAction::Pause),
+                Control::Toggle => Some(LinkAction::Toggle),
+                Control::Stop => Some(LinkAction::Stop),
+                Control::Seek { .. }
+                | Control::Nudge { .. }
+                | Control::LineBack
+                | Control::Speed { .. }
+                | Control::ToggleMirror => None,
+            },
+        }
+    }
+
+    pub fn parse(link: &str) -> Result<Link, String> {
+        let url = Url::parse(link).map_err(|e| format!("invalid link: {e}"))?;
+        if url.scheme() != SCHEME {
+            return Err(format!("not a {SCHEME}:// link"));
+        }
+        // `flashprompter://play` puts the action in the host,
+        // `flashprompter:play` in the path.
+        let action = url
+            .host_str()
+            .filter(|host| !host.is_empty())
+            .unwrap_or_else(|| url.path().trim_matches('/'))
+            .to_ascii_lowercase();
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+                .filter(|value| !value.trim().is_empty())
+        };
+        match action.as_str() {
+            "play" => Ok(Link::Control(Control::Play)),
+            "pause" => Ok(Link::Control(Control::Pause)),
+            "toggle" => Ok(Link::Control(Control::Toggle)),
+            "stop" => Ok(Link::Control(Control::Stop)),
+            "load" => {
+                let script = param("script").ok_or("load needs a script parameter")?;
+                Ok(Link::Load { script })
+            }
+            "open" => {
+                let target = param("url").ok_or("open needs a url parameter")?;
+                let url = Url::parse(&target).map_err(|e| format!("invalid url: {e}"))?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    return Err("open only accepts http and https urls".into());
+                }
+                Ok(Link::Open { url })
+            }
+            other => Err(format!("unknown action: {other}")),
+        }
+    }
+}
+
+pub fn is_link(arg: &str) -> bool {
+    arg.get(..SCHEME.len())
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case(SCHEME))
+        && arg[SCHEME.len()..].starts_with(':')
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rejected<'a> {
+    link: &'a str,
+    reason: String,
+}
+
+fn reject(app: &AppHandle, link: &str, reason: String) {
+    let _ = app.emit(REJECTED_EVENT, Rejected { link, reason });
+}
+
+fn load_script(app: &AppHandle, wanted: &str) -> Result<(), String> {
+    let library = app.state::<ScriptLibrary>();
+    let id = library
+        .list()?
+        .into_iter()
+        .find(|script| script.id == wanted || script.title == wanted)
+        .map(|script| script.id)
+        .ok_or_else(|| format!("no script named {wanted}"))?;
+    scripts::open_in_editor(app, library.load(&id)?);
+    Ok(())
+}
+
+async fn download(url: Url) -> Result<OpenedFile, String> {
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+    if response
+        .content_length()
+        .is_some_and(|length| length as usize > MAX_DOWNLOAD)
+    {
+        return Err("script is too large".into());
+    }
+    // Read in chunks, since the length may be missing or wrong.
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if bytes.len() + chunk.len() > MAX_DOWNLOAD {
+            return Err("script is too large".into());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    let (text, encoding) = script_file::decode(&bytes);
+    let title = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("script")
+        .to_string();
+    Ok(OpenedFile {
+        path: PathBuf::from(url.as_str()),
+        title,
+        text,
+        encoding: encoding.name().to_string(),
+    })
+}
+
+/// Runs `link` if its action is allowed. Refusals and failures are reported
+/// through `REJECTED_EVENT`.
+pub fn handle(app: &AppHandle, link: &str) {
+    let parsed = match Link::parse(link) {
+        Ok(parsed) => parsed,
+        Err(reason) => return reject(app, link, reason),
+    };
+    let Some(action) = parsed.action() else {
+        return reject(app, link, "links cannot trigger this action".into());
+    };
+    let allowlist = app.state::<Config>().get().link_allowlist;
+    if !allowlist.0.contains(&action) {
+        return reject(app, link, format!("{action:?} links are turned off"));
+    }
+    let result = match parsed {
+        Link::Control(control) => app.state::<Arc<AppControl>>().apply(control).map(|_| ()),
+        Link::Load { script } => load_script(app, &script),
+        Link::Open { url } => {
+            let app = app.clone();
+            let link = link.to_string();
+            tauri::async_runtime::spawn(async move {
+                match download(url).await {
+                    Ok(file) => launch::show_file(&app, file),
+                    Err(reason) => reject(&app, &link, reason),
+                }
+            });
+            Ok(())
+        }
+    };
+    if let Err(reason) = result {
+        reject(app, link, reason);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_supported_links() {
+        assert_eq!(
+            Link::parse("flashprompter://play").unwrap(),
+            Link::Control(Control::Play)
+        );
+        assert_eq!(
+            Link::parse("flashprompter:toggle").unwrap(),
+            Link::Control(Control::Toggle)
+        );
+        assert_eq!(
+            Link::parse("flashprompter://load?script=weekly-update").unwrap(),
+            Link::Load {
+                script: "weekly-update".into()
+            }
+        );
+        let open =
+            Link::parse("flashprompter://open?url=https%3A%2F%2Fdocs.example.com%2Fintro.txt")
+                .unwrap();
+        assert_eq!(
+            open,
+            Link::Open {
+                url: Url::parse("https://docs.example.com/intro.txt").unwrap()
+            }
+        );
+        assert_eq!(open.action(), Some(LinkAction::Open));
+        assert_eq!(
+            Link::Control(Control::Toggle).action(),
+            Some(LinkAction::Toggle)
+        );
+        assert_eq!(Link::Control(Control::LineBack).action(), None);
+        assert_eq!(
+            Link::Control(Control::Seek { progress: 0.5 }).action(),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_links() {
+        assert!(Link::parse("flashprompter://open?url=file%3A%2F%2F%2Fetc%2Fpasswd").is_err());
+        assert!(Link::parse("flashprompter://load").is_err());
+        assert!(Link::parse("flashprompter://format-disk").is_err());
+        assert!(Link::parse("https://example.com/play").is_err());
+        assert!(is_link("FlashPrompter://play"));
+        assert!(!is_link("notes/flashprompter.txt"));
+        assert!(!is_link("每周更新稿件.txt"));
+    }
+
+    /// Serves one response with a chunked body of `total` bytes.
+    fn serve_chunked(total: usize) -> Url {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0; 4096]);
+            let _ = stream.write_all(
+                b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+            );
+            let chunk = vec![b'a'; 64 * 1024];
+            let mut sent = 0;
+            while sent < total {
+                let size = chunk.len().min(total - sent);
+                let head = format!("{size:x}\r\n");
+                let written = stream
+                    .write_all(head.as_bytes())
+                    .and_then(|_| stream.write_all(&chunk[..size]))
+                    .and_then(|_| stream.write_all(b"\r\n"));
+                if written.is_err() {
+                    return;
+                }
+                sent += size;
+            }
+            let _ = stream.write_all(b"0\r\n\r\n");
+        });
+        Url::parse(&format!("http://127.0.0.1:{port}/intro.txt")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn downloads_are_limited_without_a_content_length() {
+        let file = download(serve_chunked(1000)).await.unwrap();
+        assert_eq!((file.title.as_str(), file.text.len()), ("intro.txt", 1000));
+        let error = download(serve_chunked(MAX_DOWNLOAD + 1)).await.unwrap_err();
+        assert_eq!(error, "script is too large");
+    }
+
+    #[test]
+    fn open_is_not_allowed_by_default() {
+        let allowlist = LinkAllowlist::default();
+        assert!(!allowlist.0.contains(&LinkAction::Open));
+        assert!(allowlist.0.contains(&LinkAction::Play));
+    }
+}
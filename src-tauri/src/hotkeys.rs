@@ -0,0 +1,378 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::config::Config;
+use crate::control::{AppControl, Control, Controller};
+use crate::{profiles, tray};
+
+const RATE_STEP: f64 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+    TogglePlay,
+    Faster,
+    Slower,
+    LineBack,
+    HideWindow,
+    NextProfile,
+    ToggleMirror,
+    ToggleOverlay,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 8] = [
+        HotkeyAction::TogglePlay,
+        HotkeyAction::Faster,
+        HotkeyAction::Slower,
+        HotkeyAction::LineBack,
+        HotkeyAction::HideWindow,
+        HotkeyAction::NextProfile,
+        HotkeyAction::ToggleMirror,
+        HotkeyAction::ToggleOverlay,
+    ];
+
+    fn default_accelerator(self) -> &'static str {
+        match self {
+            HotkeyAction::TogglePlay => "CommandOrControl+Shift+Space",
+            HotkeyAction::Faster => "CommandOrControl+Shift+Up",
+            HotkeyAction::Slower => "CommandOrControl+Shift+Down",
+            HotkeyAction::LineBack => "CommandOrControl+Shift+Left",
+            HotkeyAction::HideWindow => "CommandOrControl+Shift+H",
+            HotkeyAction::NextProfile => "CommandOrControl+Shift+P",
+            HotkeyAction::ToggleMirror => "CommandOrControl+Shift+M",
+            HotkeyAction::ToggleOverlay => "CommandOrControl+Shift+O",
+        }
+    }
+}
+
+/// An action's accelerator and whether the OS accepted it. An empty
+/// accelerator means the action is unbound.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyBinding {
+    pub action: HotkeyAction,
+    pub accelerator: String,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+fn default_bindings() -> BTreeMap<HotkeyAction, String> {
+    HotkeyAction::ALL
+        .iter()
+        .map(|action| (*action, action.default_accelerator().to_string()))
+        .collect()
+}
+
+/// Returns `bindings` with `action` bound to `accelerator`, or an error if
+/// the accelerator does not parse or another action already has it.
+fn rebind(
+    bindings: &BTreeMap<HotkeyAction, String>,
+    action: HotkeyAction,
+    accelerator: &str,
+) -> Result<BTreeMap<HotkeyAction, String>, String> {
+    let accelerator = accelerator.trim();
+    if !accelerator.is_empty() {
+        let shortcut = Shortcut::from_str(accelerator).map_err(|e| e.to_string())?;
+        let conflict = bindings.iter().find(|(other, bound)| {
+            **other != action
+                && Shortcut::from_str(bound).is_ok_and(|bound| bound.id() == shortcut.id())
+        });
+        if let Some((other, _)) = conflict {
+            return Err(format!("{accelerator} is already bound to {other:?}"));
+        }
+    }
+    let mut bindings = bindings.clone();
+    bindings.insert(action, accelerator.to_string());
+    Ok(bindings)
+}
+
+/// Applies stored bindings on top of the defaults with the same checks as
+/// `rebind`. A file edited by hand that fails them is ignored as a whole.
+fn checked(stored: BTreeMap<HotkeyAction, String>) -> BTreeMap<HotkeyAction, String> {
+    let mut bindings = default_bindings();
+    bindings.extend(stored);
+    let valid = bindings
+        .iter()
+        .try_fold(bindings.clone(), |checked, (action, accelerator)| {
+            rebind(&checked, *action, accelerator)
+        });
+    match valid {
+        Ok(bindings) => bindings,
+        Err(_) => default_bindings(),
+    }
+}
+
+/// Global shortcuts persisted in `hotkeys.json` under the app config dir.
+pub struct Hotkeys {
+    path: PathBuf,
+    bindings: Mutex<BTreeMap<HotkeyAction, String>>,
+    errors: Mutex<BTreeMap<HotkeyAction, String>>,
+    /// Held for a whole rebind or reset, from reading the bindings to saving
+    /// them, so concurrent changes cannot both pass the conflict check.
+    /// `bindings` itself is only locked briefly, so the shortcut handler
+    /// never waits on the OS registering a shortcut.
+    changes: Mutex<()>,
+}
+
+impl Hotkeys {
+    pub fn load(path: PathBuf) -> Self {
+        let stored: BTreeMap<HotkeyAction, String> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            bindings: Mutex::new(checked(stored)),
+            errors: Mutex::new(BTreeMap::new()),
+            changes: Mutex::new(()),
+        }
+    }
+
+    /// Registers every binding, recording the ones the OS refuses (usually
+    /// because another application already owns the combination).
+    pub fn register_all(&self, app: &AppHandle) {
+        let bindings = self.bindings();
+        let mut errors = BTreeMap::new();
+        for (action, accelerator) in &bindings {
+            if accelerator.is_empty() {
+                continue;
+            }
+            if let Err(err) = app.global_shortcut().register(accelerator.as_str()) {
+                errors.insert(*action, err.to_string());
+            }
+        }
+        if let Ok(mut current) = self.errors.lock() {
+            *current = errors;
+        }
+    }
+
+    pub fn list(&self, app: &AppHandle) -> Vec<HotkeyBinding> {
+        let errors = self.errors.lock().map(|e| e.clone()).unwrap_or_default();
+        self.bindings()
+            .into_iter()
+            .map(|(action, accelerator)| HotkeyBinding {
+                action,
+                registered: !accelerator.is_empty()
+                    && app.global_shortcut().is_registered(accelerator.as_str()),
+                error: errors.get(&action).cloned(),
+                accelerator,
+            })
+            .collect()
+    }
+
+    pub fn set(
+        &self,
+        app: &AppHandle,
+        action: HotkeyAction,
+        accelerator: &str,
+    ) -> Result<Vec<HotkeyBinding>, String> {
+        let accelerator = accelerator.trim();
+        let _changing = self.lock_changes();
+        let current = self.bindings();
+        let previous = current.get(&action).cloned().unwrap_or_default();
+        let bindings = rebind(&current, action, accelerator)?;
+
+        if !previous.is_empty() {
+            let _ = app.global_shortcut().unregister(previous.as_str());
+        }
+        if !accelerator.is_empty() {
+            if let Err(err) = app.global_shortcut().register(accelerator) {
+                if !previous.is_empty() {
+                    let _ = app.global_shortcut().register(previous.as_str());
+                }
+                return Err(format!("{accelerator} is unavailable: {err}"));
+            }
+        }
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.remove(&action);
+        }
+        self.store(bindings)?;
+        Ok(self.list(app))
+    }
+
+    pub fn reset(&self, app: &AppHandle) -> Result<Vec<HotkeyBinding>, String> {
+        let _changing = self.lock_changes();
+        let _ = app.global_shortcut().unregister_all();
+        self.store(default_bindings())?;
+        self.register_all(app);
+        Ok(self.list(app))
+    }
+
+    fn action_for(&self, shortcut: &Shortcut) -> Option<HotkeyAction> {
+        self.bindings()
+            .into_iter()
+            .find_map(|(action, accelerator)| {
+                Shortcut::from_str(&accelerator)
+                    .is_ok_and(|bound| bound.id() == shortcut.id())
+                    .then_some(action)
+            })
+    }
+
+    fn bindings(&self) -> BTreeMap<HotkeyAction, String> {
+        self.bindings
+            .lock()
+            .map(|bindings| bindings.clone())
+            .unwrap_or_default()
+    }
+
+    fn lock_changes(&self) -> std::sync::MutexGuard<'_, ()> {
+        self.changes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Saves `bindings` and makes them current. The file is replaced in one
+    /// step, so a crash cannot leave half of it behind.
+    fn store(&self, bindings: BTreeMap<HotkeyAction, String>) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(&bindings).map_err(|e| e.to_string())?;
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &self.path).map_err(|e| e.to_string())?;
+        if let Ok(mut current) = self.bindings.lock() {
+            *current = bindings;
+        }
+        Ok(())
+    }
+}
+
+/// Handler passed to the global shortcut plugin.
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state != ShortcutState::Pressed {
+        return;
+    }
+    let Some(action) = app.state::<Hotkeys>().action_for(shortcut) else {
+        return;
+    };
+    let control = app.state::<Arc<AppControl>>();
+    match action {
+        HotkeyAction::TogglePlay => {
+            let _ = control.apply(Control::Toggle);
+        }
+        HotkeyAction::Faster | HotkeyAction::Slower => {
+            let step = if action == HotkeyAction::Faster {
+                RATE_STEP
+            } else {
+                -RATE_STEP
+            };
+            let rate = control.status().rate + step;
+            let _ = control.apply(Control::Speed { rate });
+        }
+        HotkeyAction::LineBack => {
+            let _ = control.apply(Control::LineBack);
+        }
+        HotkeyAction::ToggleMirror => {
+            let _ = control.apply(Control::ToggleMirror);
+        }
+        HotkeyAction::ToggleOverlay => {
+            let _ = app.state::<Config>().toggle_overlay();
+        }
+        HotkeyAction::NextProfile => {
+            let _ = profiles::activate(app, app.state::<Config>().next_profile());
+        }
+        HotkeyAction::HideWindow => {
+            tray::toggle_main_window(app);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_hotkeys(app: AppHandle, hotkeys: State<'_, Hotkeys>) -> Vec<HotkeyBinding> {
+    hotkeys.list(&app)
+}
+
+#[tauri::command]
+pub fn set_hotkey(
+    app: AppHandle,
+    hotkeys: State<'_, Hotkeys>,
+    action: HotkeyAction,
+    accelerator: String,
+) -> Result<Vec<HotkeyBinding>, String> {
+    hotkeys.set(&app, action, &accelerator)
+}
+
+#[tauri::command]
+pub fn reset_hotkeys(
+    app: AppHandle,
+    hotkeys: State<'_, Hotkeys>,
+) -> Result<Vec<HotkeyBinding>, String> {
+    hotkeys.reset(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_checks_for_conflicts() {
+        let defaults = default_bindings();
+        let bindings = rebind(&defaults, HotkeyAction::Faster, " Alt+F9 ").unwrap();
+        assert_eq!(bindings[&HotkeyAction::Faster], "Alt+F9");
+        // The old combination is free again.
+        let bindings = rebind(
+            &bindings,
+            HotkeyAction::Slower,
+            HotkeyAction::Faster.default_accelerator(),
+        )
+        .unwrap();
+
+        let error = rebind(&bindings, HotkeyAction::TogglePlay, "alt+f9").unwrap_err();
+        assert_eq!(error, "alt+f9 is already bound to Faster");
+        assert!(rebind(&bindings, HotkeyAction::Faster, "Alt+F9").is_ok());
+        assert!(rebind(&bindings, HotkeyAction::Faster, "Alt+Nope").is_err());
+
+        let unbound = rebind(&bindings, HotkeyAction::Faster, "").unwrap();
+        let unbound = rebind(&unbound, HotkeyAction::Slower, "").unwrap();
+        assert_eq!(unbound[&HotkeyAction::Slower], "");
+    }
+
+    #[test]
+    fn stored_bindings_with_conflicts_fall_back_to_the_defaults() {
+        let stored = BTreeMap::from([(HotkeyAction::LineBack, "Alt+F9".to_string())]);
+        let bindings = checked(stored);
+        assert_eq!(bindings[&HotkeyAction::LineBack], "Alt+F9");
+        assert_eq!(
+            bindings[&HotkeyAction::TogglePlay],
+            HotkeyAction::TogglePlay.default_accelerator()
+        );
+
+        let conflicting = BTreeMap::from([
+            (HotkeyAction::Faster, "Alt+F9".to_string()),
+            (HotkeyAction::Slower, "Alt+F9".to_string()),
+        ]);
+        assert_eq!(checked(conflicting), default_bindings());
+        let shadowing = BTreeMap::from([(
+            HotkeyAction::Faster,
+            HotkeyAction::TogglePlay.default_accelerator().to_string(),
+        )]);
+        assert_eq!(checked(shadowing), default_bindings());
+        let invalid = BTreeMap::from([(HotkeyAction::Faster, "Hyper+?".to_string())]);
+        assert_eq!(checked(invalid), default_bindings());
+    }
+
+    #[test]
+    fn stored_bindings_are_replaced_whole() {
+        let dir =
+            std::env::temp_dir().join(format!("flash-prompter-hotkeys-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("hotkeys.json");
+        let hotkeys = Hotkeys::load(path.clone());
+        let bindings = rebind(&hotkeys.bindings(), HotkeyAction::LineBack, "Alt+F9").unwrap();
+        hotkeys.store(bindings.clone()).unwrap();
+
+        assert_eq!(hotkeys.bindings(), bindings);
+        assert_eq!(Hotkeys::load(path.clone()).bindings(), bindings);
+        assert!(!path.with_extension("tmp").exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
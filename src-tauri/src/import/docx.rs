@@ -0,0 +1,324 @@
+//! Word (`.docx`) documents to script markup.
+//!
+//! - Each paragraph becomes a line. Headings, meaning the built-in heading
+//!   and title styles or any paragraph with an outline level, become
+//!   `[section ...]` markers.
+//! - Bold and italic runs become `**emphasis**`. Hidden text is dropped.
+//! - Comments become operator notes where the commented range ends.
+//! - Tracked changes are read as accepted: insertions are kept, while
+//!   deletions and the old formatting of changed text are dropped.
+//! - Field codes and text boxes are not read.
+
+use std::collections::{HashMap, HashSet};
+
+use super::office::{self, Node, Script};
+
+/// Elements whose content is not part of the accepted text.
+const SKIPPED: [&str; 6] = [
+    "del",
+    "moveFrom",
+    "rPrChange",
+    "pPrChange",
+    "instrText",
+    "txbxContent",
+];
+
+fn is_heading_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name == "title" || name.starts_with("heading")
+}
+
+fn is_outline_level(node: &Node) -> bool {
+    // Level 9 is body text.
+    node.attribute("val")
+        .and_then(|level| level.parse::<u8>().ok())
+        .is_some_and(|level| level < 9)
+}
+
+/// Toggle properties such as `<w:b/>` are on unless their value says off.
+fn is_on(node: &Node) -> bool {
+    node.attribute("val")
+        .is_none_or(|value| !matches!(value, "0" | "false" | "off" | "none"))
+}
+
+/// Style ids that are headings. Localized Word versions name style ids after
+/// the UI language, so the style name and outline level are checked too.
+fn heading_styles(xml: &str) -> Result<HashSet<String>, String> {
+    let mut headings = HashSet::new();
+    let mut style: Option<String> = None;
+    for node in office::nodes(xml)? {
+        match &node {
+            Node::Start { name, .. } if name == "style" => {
+                style = node.attribute("styleId").map(String::from);
+            }
+            Node::Start { name, .. } if name == "name" || name == "outlineLvl" => {
+                let is_heading = if name == "name" {
+                    node.attribute("val").is_some_and(is_heading_name)
+                } else {
+                    is_outline_level(&node)
+                };
+                if let (true, Some(id)) = (is_heading, &style) {
+                    headings.insert(id.clone());
+                }
+            }
+            Node::End { name } if name == "style" => style = None,
+            _ => {}
+        }
+    }
+    Ok(headings)
+}
+
+/// Comment texts by id.
+fn comments(xml: &str) -> Result<HashMap<String, String>, String> {
+    let mut comments = HashMap::new();
+    let mut comment: Option<(String, String)> = None;
+    let mut in_text = false;
+    for node in office::nodes(xml)? {
+        match &node {
+            Node::Start { name, .. } if name == "comment" => {
+                comment = node
+                    .attribute("id")
+                    .map(|id| (id.to_string(), String::new()));
+            }
+            Node::Start { name, .. } if name == "t" => in_text = true,
+            Node::End { name } if name == "t" => in_text = false,
+            Node::Text(text) if in_text => {
+                if let Some((_, body)) = &mut comment {
+                    body.push_str(text);
+                }
+            }
+            Node::End { name } if name == "p" => {
+                if let Some((_, body)) = &mut comment {
+                    body.push(' ');
+                }
+            }
+            Node::End { name } if name == "comment" => {
+                if let Some((id, body)) = comment.take() {
+                    comments.insert(id, body);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(comments)
+}
+
+#[derive(Default)]
+struct Run {
+    bold: bool,
+    italic: bool,
+    hidden: bool,
+}
+
+struct Converter<'a> {
+    headings: &'a HashSet<String>,
+    comments: &'a HashMap<String, String>,
+    script: Script,
+    skip: usize,
+    in_paragraph_props: bool,
+    in_run_props: bool,
+    in_text: bool,
+    run: Run,
+    /// Title of the current paragraph, when it is a heading.
+    heading: Option<String>,
+    /// Comments on a heading, written after its section marker.
+    heading_notes: Vec<String>,
+}
+
+impl Converter<'_> {
+    fn node(&mut self, node: &Node) {
+        match node {
+            Node::Start { name, .. } if SKIPPED.contains(&name.as_str()) => self.skip += 1,
+            Node::End { name } if SKIPPED.contains(&name.as_str()) => {
+                self.skip = self.skip.saturating_sub(1);
+            }
+            _ if self.skip > 0 => {}
+            Node::Start { name, .. } => self.start(name, node),
+            Node::End { name } => self.end(name),
+            Node::Text(text) if self.in_text && !self.run.hidden => self.write(text),
+            Node::Text(_) => {}
+        }
+    }
+
+    fn start(&mut self, name: &str, node: &Node) {
+        match name {
+            "pPr" => self.in_paragraph_props = true,
+            "pStyle" if self.in_paragraph_props => {
+                let id = node.attribute("val").unwrap_or_default();
+                if self.headings.contains(id) || is_heading_name(id) {
+                    self.heading = Some(String::new());
+                }
+            }
+            "outlineLvl" if self.in_paragraph_props && is_outline_level(node) => {
+                self.heading.get_or_insert_with(String::new);
+            }
+            // Tab stops are declared in paragraph properties.
+            _ if self.in_paragraph_props => {}
+            "r" => self.run = Run::default(),
+            "rPr" => self.in_run_props = true,
+            "b" if self.in_run_props => self.run.bold = is_on(node),
+            "i" if self.in_run_props => self.run.italic = is_on(node),
+            "vanish" if self.in_run_props => self.run.hidden = is_on(node),
+            "t" => self.in_text = true,
+            "tab" => self.write(" "),
+            "noBreakHyphen" => self.write("-"),
+            "br" | "cr" => match &mut self.heading {
+                Some(title) => title.push(' '),
+                None => self.script.end_line(),
+            },
+            "commentReference" => {
+                let Some(text) = node.attribute("id").and_then(|id| self.comments.get(id)) else {
+                    return;
+                };
+                if self.heading.is_some() {
+                    self.heading_notes.push(text.clone());
+                } else {
+                    self.script.note(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        match name {
+            "pPr" => self.in_paragraph_props = false,
+            "rPr" => self.in_run_props = false,
+            "t" => self.in_text = false,
+            "p" => {
+                if let Some(title) = self.heading.take() {
+                    self.script.section(&title);
+                    for note in std::mem::take(&mut self.heading_notes) {
+                        self.script.note(&note);
+                    }
+                }
+                self.script.end_line();
+            }
+            _ => {}
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.run.hidden {
+            return;
+        }
+        match &mut self.heading {
+            Some(title) => title.push_str(text),
+            None => self.script.text(text, self.run.bold || self.run.italic),
+        }
+    }
+}
+
+fn convert(
+    document: &str,
+    headings: &HashSet<String>,
+    comments: &HashMap<String, String>,
+) -> Result<String, String> {
+    let mut converter = Converter {
+        headings,
+        comments,
+        script: Script::default(),
+        skip: 0,
+        in_paragraph_props: false,
+        in_run_props: false,
+        in_text: false,
+        run: Run::default(),
+        heading: None,
+        heading_notes: Vec::new(),
+    };
+    for node in office::nodes(document)? {
+        converter.node(&node);
+    }
+    Ok(converter.script.finish())
+}
+
+/// Converts the bytes of a `.docx` file to script markup.
+pub fn to_script(bytes: &[u8]) -> Result<String, String> {
+    let mut archive = office::open(bytes)?;
+    let document =
+        office::read_part(&mut archive, "word/document.xml")?.ok_or("not a Word document")?;
+    let headings = match office::read_part(&mut archive, "word/styles.xml")? {
+        Some(xml) => heading_styles(&xml)?,
+        None => HashSet::new(),
+    };
+    let comments = match office::read_part(&mut archive, "word/comments.xml")? {
+        Some(xml) => comments(&xml)?,
+        None => HashMap::new(),
+    };
+    convert(&document, &headings, &comments)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    use super::*;
+
+    const DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p>
+      <w:pPr><w:pStyle w:val="1"/><w:tabs><w:tab w:val="left" w:pos="720"/></w:tabs></w:pPr>
+      <w:r><w:t>开场</w:t></w:r>
+      <w:r><w:commentReference w:id="0"/></w:r>
+    </w:p>
+    <w:p>
+      <w:r><w:t xml:space="preserve">大家好，&#x200B;欢迎收看 </w:t></w:r>
+      <w:r><w:rPr><w:b/></w:rPr><w:t>周更新</w:t></w:r>
+      <w:r><w:rPr><w:i w:val="0"/><w:rPrChange><w:rPr><w:i/></w:rPr></w:rPrChange></w:rPr><w:t>。</w:t></w:r>
+      <w:del><w:r><w:delText>上周的内容</w:delText></w:r></w:del>
+      <w:ins><w:r><w:t>今天聊 [三件事]</w:t></w:r></w:ins>
+      <w:r><w:commentReference w:id="1"/></w:r>
+    </w:p>
+    <w:p/>
+    <w:p/>
+    <w:p>
+      <w:r><w:fldChar w:fldCharType="begin"/></w:r>
+      <w:r><w:instrText> PAGE </w:instrText></w:r>
+      <w:r><w:t>第一行</w:t><w:br/><w:t>第二行</w:t></w:r>
+      <w:r><w:rPr><w:vanish/></w:rPr><w:t>隐藏</w:t></w:r>
+    </w:p>
+  </w:body>
+</w:document>"#;
+
+    const STYLES: &str = r#"<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:style w:type="paragraph" w:styleId="1"><w:name w:val="heading 1"/></w:style>
+  <w:style w:type="paragraph" w:styleId="a"><w:name w:val="Normal"/></w:style>
+</w:styles>"#;
+
+    const COMMENTS: &str = r#"<w:comments xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:comment w:id="0" w:author="导演"><w:p><w:r><w:t>灯光就位</w:t></w:r></w:p></w:comment>
+  <w:comment w:id="1" w:author="导演"><w:p><w:r><w:t>这里停顿</w:t></w:r></w:p><w:p><w:r><w:t>看镜头</w:t></w:r></w:p></w:comment>
+</w:comments>"#;
+
+    const EXPECTED: &str = "[section 开场]\n\n\
+        [note: 灯光就位]\n\
+        大家好，欢迎收看 **周更新**。今天聊 \\[三件事\\][note: 这里停顿 看镜头]\n\n\
+        第一行\n第二行";
+
+    #[test]
+    fn converts_paragraphs_headings_comments_and_changes() {
+        let headings = heading_styles(STYLES).unwrap();
+        let comments = comments(COMMENTS).unwrap();
+        assert_eq!(convert(DOCUMENT, &headings, &comments).unwrap(), EXPECTED);
+    }
+
+    #[test]
+    fn reads_the_archive() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, xml) in [
+            ("word/document.xml", DOCUMENT),
+            ("word/styles.xml", STYLES),
+            ("word/comments.xml", COMMENTS),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        let bytes = zip.finish().unwrap().into_inner();
+        assert_eq!(to_script(&bytes).unwrap(), EXPECTED);
+        assert!(to_script(b"plain text").is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Markdown to script markup.
+//!
+//! - Headings become `[section ...]` markers.
+//! - Paragraphs keep their line breaks and are separated by a blank line.
+//! - List items become lines of their own, without bullets or numbers.
+//! - Bold and italic become `**emphasis**`.
+//! - HTML comments become operator notes. Code blocks, images, struck-out
+//!   text and other HTML are dropped; inline code and link text are kept.
+//!
+//! Characters that mean something in script markup are escaped.
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+use super::escape;
+
+#[derive(Default)]
+struct Writer {
+    lines: Vec<String>,
+    line: String,
+    /// Nesting of bold and italic spans.
+    emphasis: usize,
+    /// Whether `**` is open on the current line.
+    emphasis_open: bool,
+    list_depth: usize,
+    /// Nesting of content that is dropped.
+    skip: usize,
+    heading: Option<String>,
+    html_block: bool,
+    /// An HTML comment that has been opened but not closed yet.
+    comment: Option<String>,
+}
+
+impl Writer {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            _ if self.skip > 0 => {}
+            Event::Html(html) => self.html(&html),
+            Event::InlineHtml(html) => self.html(&html),
+            Event::Text(text) | Event::Code(text) => self.text(&text),
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some(comment) = &mut self.comment {
+                    comment.push(' ');
+                } else if let Some(heading) = &mut self.heading {
+                    heading.push(' ');
+                } else {
+                    self.end_line();
+                }
+            }
+            Event::Rule => self.block(),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::CodeBlock(_)
+            | Tag::Image { .. }
+            | Tag::Strikethrough
+            | Tag::FootnoteDefinition(_)
+            | Tag::MetadataBlock(_) => self.skip += 1,
+            _ if self.skip > 0 => {}
+            Tag::Heading { .. } => {
+                self.block();
+                self.heading = Some(String::new());
+            }
+            Tag::Paragraph => {
+                if self.list_depth == 0 {
+                    self.block();
+                } else {
+                    self.end_line();
+                }
+            }
+            Tag::List(_) => {
+                if self.list_depth == 0 {
+                    self.block();
+                } else {
+                    self.end_line();
+                }
+                self.list_depth += 1;
+            }
+            Tag::Item => self.end_line(),
+            Tag::HtmlBlock => {
+                self.block();
+                self.html_block = true;
+            }
+            Tag::Emphasis | Tag::Strong => self.emphasis += 1,
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::CodeBlock
+            | TagEnd::Image
+            | TagEnd::Strikethrough
+            | TagEnd::FootnoteDefinition
+            | TagEnd::MetadataBlock(_) => self.skip -= 1,
+            _ if self.skip > 0 => {}
+            TagEnd::Heading(_) => {
+                let title = self.heading.take().unwrap_or_default();
+                let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !title.is_empty() {
+                    self.lines.push(format!("[section {}]", escape(&title)));
+                }
+            }
+            TagEnd::Paragraph | TagEnd::Item => self.end_line(),
+            TagEnd::List(_) => {
+                self.end_line();
+                self.list_depth -= 1;
+            }
+            TagEnd::HtmlBlock => {
+                self.end_line();
+                self.html_block = false;
+            }
+            TagEnd::Emphasis | TagEnd::Strong => {
+                self.emphasis -= 1;
+                if self.emphasis == 0 && self.emphasis_open {
+                    self.line.push_str("**");
+                    self.emphasis_open = false;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(comment) = &mut self.comment {
+            comment.push_str(text);
+        } else if let Some(heading) = &mut self.heading {
+            heading.push_str(text);
+        } else {
+            if self.emphasis > 0 && !self.emphasis_open {
+                self.line.push_str("**");
+                self.emphasis_open = true;
+            }
+            self.line.push_str(&escape(text));
+        }
+    }
+
+    /// Keeps the comments in `html`, which may open or close a comment that
+    /// spans several events.
+    fn html(&mut self, html: &str) {
+        let mut rest = html;
+        loop {
+            if let Some(comment) = &mut self.comment {
+                let Some(end) = rest.find("-->") else {
+                    comment.push_str(rest);
+                    return;
+                };
+                comment.push_str(&rest[..end]);
+                let comment = self.comment.take().unwrap_or_default();
+                self.note(&comment);
+                rest = &rest[end + 3..];
+            } else {
+                let Some(start) = rest.find("<!--") else {
+                    return;
+                };
+                self.comment = Some(String::new());
+                rest = &rest[start + 4..];
+            }
+        }
+    }
+
+    fn note(&mut self, text: &str) {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            return;
+        }
+        if self.html_block {
+            self.end_line();
+        }
+        // Notes cannot sit inside emphasis; the next text reopens it.
+        if self.emphasis_open {
+            self.line.push_str("**");
+            self.emphasis_open = false;
+        }
+        self.line.push_str(&format!("[note: {}]", escape(&text)));
+        if self.html_block {
+            self.end_line();
+        }
+    }
+
+    fn end_line(&mut self) {
+        if self.emphasis_open {
+            self.line.push_str("**");
+            self.emphasis_open = false;
+        }
+        let line = std::mem::take(&mut self.line);
+        let line = line.trim();
+        if !line.is_empty() {
+            self.lines.push(line.to_string());
+        }
+    }
+
+    /// Starts a new block, separated from the previous one by a blank line.
+    fn block(&mut self) {
+        self.end_line();
+        if self.list_depth == 0 && self.lines.last().is_some_and(|line| !line.is_empty()) {
+            self.lines.push(String::new());
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.end_line();
+        while self.lines.last().is_some_and(|line| line.is_empty()) {
+            self.lines.pop();
+        }
+        self.lines.join("\n")
+    }
+}
+
+/// Converts Markdown to script markup.
+pub fn to_script(markdown: &str) -> String {
+    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut writer = Writer::default();
+    for event in Parser::new_ext(markdown, options) {
+        writer.event(event);
+    }
+    writer.finish()
+}
+
+#[tauri::command]
+pub fn import_markdown(text: String) -> String {
+    to_script(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+    use crate::markup;
+
+    /// Each `<name>.md` in the fixtures directory is converted and compared
+    /// with `<name>.txt`. Set `UPDATE_FIXTURES=1` to rewrite the expected
+    /// files after an intended change.
+    #[test]
+    fn matches_golden_files() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/markdown");
+        let update = std::env::var_os("UPDATE_FIXTURES").is_some();
+        let mut checked = 0;
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            let script = to_script(&fs::read_to_string(&path).unwrap());
+            let expected_path = path.with_extension("txt");
+            if update {
+                fs::write(&expected_path, format!("{script}\n")).unwrap();
+            }
+            let expected = fs::read_to_string(&expected_path).unwrap();
+            assert_eq!(
+                script,
+                expected.trim_end_matches('\n'),
+                "{}",
+                path.display()
+            );
+            let errors = markup::parse(&script).errors;
+            assert!(errors.is_empty(), "{}: {errors:?}", path.display());
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn keeps_emphasis_within_a_line() {
+        assert_eq!(
+            to_script("**第一行\n第二行** and *[pause 1s]*"),
+            "**第一行**\n**第二行** and **\\[pause 1s\\]**"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Importers that turn documents written elsewhere into script markup (see
+//! [`markup`](crate::markup)), so formatting syntax is not read aloud.
+
+pub mod docx;
+pub mod markdown;
+pub mod odt;
+mod office;
+pub mod subtitles;
+
+/// Escapes characters that mean something in script markup.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '[' | ']' | '*' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,305 @@
+//! OpenDocument (`.odt`) documents to script markup, with the same rules as
+//! [`docx`](super::docx): paragraphs become lines, headings become
+//! `[section ...]` markers, bold and italic become `**emphasis**`, comments
+//! become operator notes and tracked changes are read as accepted.
+
+use std::collections::HashMap;
+
+use super::office::{self, Node, Script};
+
+/// Elements whose content is not read: the deleted text of tracked changes,
+/// footnotes, frames, generated indexes and comment metadata.
+const SKIPPED: [&str; 7] = [
+    "tracked-changes",
+    "note",
+    "frame",
+    "table-of-content",
+    "number",
+    "creator",
+    "date",
+];
+
+#[derive(Default)]
+struct Style {
+    parent: Option<String>,
+    emphasis: bool,
+    heading: bool,
+}
+
+/// Named and automatic styles, which may inherit from a parent style.
+#[derive(Default)]
+struct Styles(HashMap<String, Style>);
+
+impl Styles {
+    fn read(&mut self, xml: &str) -> Result<(), String> {
+        let mut current: Option<String> = None;
+        for node in office::nodes(xml)? {
+            match &node {
+                Node::Start { name, .. } if name == "style" => {
+                    let Some(style_name) = node.attribute("name") else {
+                        continue;
+                    };
+                    let display = node.attribute("display-name").unwrap_or(style_name);
+                    let display = display.replace("_20_", " ").to_ascii_lowercase();
+                    let style = Style {
+                        parent: node.attribute("parent-style-name").map(String::from),
+                        emphasis: false,
+                        heading: node.attribute("default-outline-level").is_some()
+                            || display == "title"
+                            || display.starts_with("heading "),
+                    };
+                    self.0.insert(style_name.to_string(), style);
+                    current = Some(style_name.to_string());
+                }
+                Node::Start { name, .. } if name == "text-properties" => {
+                    let Some(style) = current.as_ref().and_then(|name| self.0.get_mut(name)) else {
+                        continue;
+                    };
+                    let bold = ["font-weight", "font-weight-asian"].iter().any(|key| {
+                        node.attribute(key).is_some_and(|weight| {
+                            matches!(weight, "bold" | "600" | "700" | "800" | "900")
+                        })
+                    });
+                    let italic = ["font-style", "font-style-asian"].iter().any(|key| {
+                        node.attribute(key)
+                            .is_some_and(|style| matches!(style, "italic" | "oblique"))
+                    });
+                    style.emphasis = bold || italic;
+                }
+                Node::End { name } if name == "style" => current = None,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Looks `property` up on the style and its parents.
+    fn inherited(&self, name: Option<&str>, property: fn(&Style) -> bool) -> bool {
+        let mut name = name;
+        // Bounded, in case a broken file makes a style its own ancestor.
+        for _ in 0..16 {
+            let Some(style) = name.and_then(|name| self.0.get(name)) else {
+                return false;
+            };
+            if property(style) {
+                return true;
+            }
+            name = style.parent.as_deref();
+        }
+        false
+    }
+}
+
+struct Converter<'a> {
+    styles: &'a Styles,
+    script: Script,
+    skip: usize,
+    in_paragraph: bool,
+    /// Emphasis of the paragraph and each open span.
+    emphasis: Vec<bool>,
+    heading: Option<String>,
+    heading_notes: Vec<String>,
+    /// Text of the comment being read.
+    annotation: Option<String>,
+}
+
+impl Converter<'_> {
+    fn node(&mut self, node: &Node) {
+        match node {
+            Node::Start { name, .. } if SKIPPED.contains(&name.as_str()) => self.skip += 1,
+            Node::End { name } if SKIPPED.contains(&name.as_str()) => {
+                self.skip = self.skip.saturating_sub(1);
+            }
+            _ if self.skip > 0 => {}
+            Node::Start { name, .. } => self.start(name, node),
+            Node::End { name } => self.end(name),
+            Node::Text(text) => {
+                let text = collapse_whitespace(text);
+                self.write(&text);
+            }
+        }
+    }
+
+    fn start(&mut self, name: &str, node: &Node) {
+        let style = node.attribute("style-name");
+        match name {
+            "annotation" => self.annotation = Some(String::new()),
+            // Paragraphs inside a comment are part of its text.
+            _ if self.annotation.is_some() => {}
+            "p" | "h" => {
+                self.in_paragraph = true;
+                if name == "h" || self.styles.inherited(style, |style| style.heading) {
+                    self.heading = Some(String::new());
+                }
+                self.emphasis = vec![self.styles.inherited(style, |style| style.emphasis)];
+            }
+            "span" | "a" => {
+                let emphasis = self.styles.inherited(style, |style| style.emphasis)
+                    || self.emphasis.last().copied().unwrap_or_default();
+                self.emphasis.push(emphasis);
+            }
+            "s" => {
+                let count = node
+                    .attribute("c")
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(1usize);
+                self.write(&" ".repeat(count.min(16)));
+            }
+            "tab" => self.write(" "),
+            "line-break" => match &mut self.heading {
+                Some(title) => title.push(' '),
+                None if self.in_paragraph => self.script.end_line(),
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        match name {
+            "annotation" => {
+                let text = self.annotation.take().unwrap_or_default();
+                if self.heading.is_some() {
+                    self.heading_notes.push(text);
+                } else {
+                    self.script.note(&text);
+                }
+            }
+            "p" if self.annotation.is_some() => {
+                if let Some(text) = &mut self.annotation {
+                    text.push(' ');
+                }
+            }
+            _ if self.annotation.is_some() => {}
+            "p" | "h" => {
+                if let Some(title) = self.heading.take() {
+                    self.script.section(&title);
+                    for note in std::mem::take(&mut self.heading_notes) {
+                        self.script.note(&note);
+                    }
+                }
+                self.script.end_line();
+                self.in_paragraph = false;
+                self.emphasis.clear();
+            }
+            "span" | "a" => {
+                self.emphasis.pop();
+            }
+            _ => {}
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        if let Some(annotation) = &mut self.annotation {
+            annotation.push_str(text);
+        } else if let Some(title) = &mut self.heading {
+            title.push_str(text);
+        } else if self.in_paragraph {
+            let emphasis = self.emphasis.last().copied().unwrap_or_default();
+            self.script.text(text, emphasis);
+        }
+    }
+}
+
+/// Runs of spaces, tabs and newlines in OpenDocument text count as one space;
+/// spaces that matter are written as `<text:s/>`.
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut last_was_space = false;
+    for c in text.chars() {
+        let is_space = matches!(c, ' ' | '\t' | '\r' | '\n');
+        if !(is_space && last_was_space) {
+            collapsed.push(if is_space { ' ' } else { c });
+        }
+        last_was_space = is_space;
+    }
+    collapsed
+}
+
+fn convert(content: &str, styles: &Styles) -> Result<String, String> {
+    let mut converter = Converter {
+        styles,
+        script: Script::default(),
+        skip: 0,
+        in_paragraph: false,
+        emphasis: Vec::new(),
+        heading: None,
+        heading_notes: Vec::new(),
+        annotation: None,
+    };
+    for node in office::nodes(content)? {
+        converter.node(&node);
+    }
+    Ok(converter.script.finish())
+}
+
+/// Converts the bytes of an `.odt` file to script markup.
+pub fn to_script(bytes: &[u8]) -> Result<String, String> {
+    let mut archive = office::open(bytes)?;
+    let content =
+        office::read_part(&mut archive, "content.xml")?.ok_or("not an OpenDocument text file")?;
+    let mut styles = Styles::default();
+    if let Some(xml) = office::read_part(&mut archive, "styles.xml")? {
+        styles.read(&xml)?;
+    }
+    styles.read(&content)?;
+    convert(&content, &styles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_paragraphs_headings_comments_and_changes() {
+        let named = r#"<office:document-styles
+            xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+            xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0">
+          <office:styles>
+            <style:style style:name="Strong_20_Emphasis" style:display-name="Strong Emphasis" style:family="text">
+              <style:text-properties fo:font-weight="bold"/>
+            </style:style>
+            <style:style style:name="Heading_20_2" style:display-name="Heading 2" style:family="paragraph"/>
+          </office:styles>
+        </office:document-styles>"#;
+        let content = r#"<office:document-content
+            xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+            xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
+            xmlns:dc="http://purl.org/dc/elements/1.1/"
+            xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0">
+          <office:automatic-styles>
+            <style:style style:name="T1" style:family="text">
+              <style:text-properties style:font-style-asian="italic"/>
+            </style:style>
+            <style:style style:name="P1" style:family="paragraph" style:parent-style-name="Heading_20_2"/>
+          </office:automatic-styles>
+          <office:body>
+            <office:text>
+              <text:tracked-changes>
+                <text:changed-region text:id="ct1">
+                  <text:deletion><text:p>上周的内容</text:p></text:deletion>
+                </text:changed-region>
+              </text:tracked-changes>
+              <text:h text:outline-level="1">开场<office:annotation><dc:creator>导演</dc:creator><text:p>灯光就位</text:p></office:annotation></text:h>
+              <text:p>大家好，<text:span text:style-name="Strong_20_Emphasis">周更新</text:span><text:change text:change-id="ct1"/><text:change-start text:change-id="ct2"/>今天聊<text:s/><text:span text:style-name="T1">[三件事]</text:span><text:change-end text:change-id="ct2"/><office:annotation><text:p>这里停顿</text:p><text:p>看镜头</text:p></office:annotation></text:p>
+              <text:p/>
+              <text:p text:style-name="P1">收尾</text:p>
+              <text:p>第一行<text:line-break/>第二行&#xA0;<text:note><text:note-body><text:p>脚注</text:p></text:note-body></text:note></text:p>
+            </office:text>
+          </office:body>
+        </office:document-content>"#;
+        let mut styles = Styles::default();
+        styles.read(named).unwrap();
+        styles.read(content).unwrap();
+        assert_eq!(
+            convert(content, &styles).unwrap(),
+            "[section 开场]\n\n\
+             [note: 灯光就位]\n\
+             大家好，**周更新**今天聊 **\\[三件事\\]**[note: 这里停顿 看镜头]\n\n\
+             [section 收尾]\n\n\
+             第一行\n第二行"
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Shared reading for word-processor documents, which are zip archives of
+//! XML parts.
+
+use std::io::{Cursor, Read};
+
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use super::escape;
+
+/// Larger parts are refused rather than inflated, so a crafted file cannot
+/// exhaust memory.
+const MAX_PART: u64 = 64 * 1024 * 1024;
+
+pub type Archive<'a> = ZipArchive<Cursor<&'a [u8]>>;
+
+pub fn open(bytes: &[u8]) -> Result<Archive<'_>, String> {
+    ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("not a document archive: {e}"))
+}
+
+/// Reads an XML part, or `None` when the archive does not have it.
+pub fn read_part(archive: &mut Archive, name: &str) -> Result<Option<String>, String> {
+    let part = match archive.by_name(name) {
+        Ok(part) => part,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("{name}: {e}")),
+    };
+    if part.size() > MAX_PART {
+        return Err(format!("{name} is too large"));
+    }
+    let mut xml = String::new();
+    part.take(MAX_PART)
+        .read_to_string(&mut xml)
+        .map_err(|e| format!("{name}: {e}"))?;
+    Ok(Some(xml))
+}
+
+/// An XML event with namespace prefixes removed. Empty elements are read as
+/// a `Start` followed by an `End`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node {
+    Start {
+        name: String,
+        attributes: Vec<(String, String)>,
+    },
+    End {
+        name: String,
+    },
+    Text(String),
+}
+
+impl Node {
+    pub fn attribute(&self, wanted: &str) -> Option<&str> {
+        match self {
+            Node::Start { attributes, .. } => attributes
+                .iter()
+                .find(|(name, _)| name == wanted)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+}
+
+fn local_name(name: &str) -> String {
+    name.split_once(':')
+        .map_or(name, |(_, local)| local)
+        .to_string()
+}
+
+fn start(element: &BytesStart) -> Result<Node, String> {
+    let mut attributes = Vec::new();
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|e| e.to_string())?;
+        let value = attribute
+            .normalized_value(XmlVersion::Implicit1_0)
+            .map_err(|e| e.to_string())?;
+        attributes.push((local_name(attribute.key.as_ref()), value.into_owned()));
+    }
+    Ok(Node::Start {
+        name: local_name(element.name().as_ref()),
+        attributes,
+    })
+}
+
+pub fn nodes(xml: &str) -> Result<Vec<Node>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut nodes = Vec::new();
+    let mut text = String::new();
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        let is_text = matches!(
+            event,
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_)
+        );
+        if !is_text && !text.is_empty() {
+            nodes.push(Node::Text(std::mem::take(&mut text)));
+        }
+        match event {
+            Event::Start(element) => nodes.push(start(&element)?),
+            Event::Empty(element) => {
+                let node = start(&element)?;
+                let name = local_name(element.name().as_ref());
+                nodes.push(node);
+                nodes.push(Node::End { name });
+            }
+            Event::End(element) => nodes.push(Node::End {
+                name: local_name(element.name().as_ref()),
+            }),
+            Event::Text(content) => text.push_str(&content.xml10_content()),
+            Event::CData(content) => text.push_str(&content.xml10_content()),
+            Event::GeneralRef(reference) => {
+                if let Some(c) = reference.resolve_char_ref().map_err(|e| e.to_string())? {
+                    text.push(c);
+                } else if let Some(entity) = resolve_predefined_entity(&reference) {
+                    text.push_str(entity);
+                }
+            }
+            Event::Eof => return Ok(nodes),
+            _ => {}
+        }
+    }
+}
+
+/// Drops the invisible characters word processors leave in text and turns
+/// non-breaking spaces into plain ones.
+fn clean(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            '\u{00A0}' | '\u{2007}' | '\u{202F}' | '\t' | '\n' | '\r' => Some(' '),
+            '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{2060}' | '\u{FEFF}' => None,
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+fn collapse(text: &str) -> String {
+    clean(text).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Writes script markup one paragraph per line. Empty paragraphs become a
+/// single blank line.
+#[derive(Default)]
+pub struct Script {
+    lines: Vec<String>,
+    line: String,
+    emphasis_open: bool,
+}
+
+impl Script {
+    pub fn text(&mut self, text: &str, emphasis: bool) {
+        let text = clean(text);
+        if text.is_empty() {
+            return;
+        }
+        if emphasis != self.emphasis_open && !text.trim().is_empty() {
+            self.line.push_str("**");
+            self.emphasis_open = emphasis;
+        }
+        self.line.push_str(&escape(&text));
+    }
+
+    pub fn note(&mut self, text: &str) {
+        let text = collapse(text);
+        if text.is_empty() {
+            return;
+        }
+        self.close_emphasis();
+        self.line.push_str(&format!("[note: {}]", escape(&text)));
+    }
+
+    fn close_emphasis(&mut self) {
+        if self.emphasis_open {
+            self.line.push_str("**");
+            self.emphasis_open = false;
+        }
+    }
+
+    fn blank_line(&mut self) {
+        if self.lines.last().is_some_and(|line| !line.is_empty()) {
+            self.lines.push(String::new());
+        }
+    }
+
+    pub fn end_line(&mut self) {
+        self.close_emphasis();
+        let line = std::mem::take(&mut self.line);
+        let line = line.trim();
+        if line.is_empty() {
+            self.blank_line();
+        } else {
+            self.lines.push(line.to_string());
+        }
+    }
+
+    /// Writes a section marker, set apart by blank lines.
+    pub fn section(&mut self, title: &str) {
+        self.end_line();
+        let title = collapse(title);
+        if title.is_empty() {
+            return;
+        }
+        self.blank_line();
+        self.lines.push(format!("[section {}]", escape(&title)));
+        self.blank_line();
+    }
+
+    pub fn finish(mut self) -> String {
+        self.end_line();
+        while self.lines.last().is_some_and(|line| line.is_empty()) {
+            self.lines.pop();
+        }
+        self.lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleans_text_and_keeps_emphasis_per_line() {
+        let mut script = Script::default();
+        script.text("\u{FEFF}大家\u{200B}好，", false);
+        script.text("欢迎\u{00A0}收看", true);
+        script.note("  镜头\n切换 ");
+        script.end_line();
+        script.end_line();
+        script.end_line();
+        script.section("第二部分");
+        script.text("5 * 3", false);
+        assert_eq!(
+            script.finish(),
+            "大家好，**欢迎 收看**[note: 镜头 切换]\n\n[section 第二部分]\n\n5 \\* 3"
+        );
+    }
+}
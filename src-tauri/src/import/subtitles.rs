@@ -0,0 +1,208 @@
+//! SubRip (`.srt`) and WebVTT (`.vtt`) subtitles to script markup.
+//!
+//! Each cue becomes its text lines, the first one starting with an
+//! `[at start-end]` cue, so the script plays back on the subtitle times.
+//! `<i>` and `<b>` become `**emphasis**`, WebVTT voice tags (`<v Anna>`)
+//! become operator notes and other tags, including SSA overrides such as
+//! `{\an8}`, are dropped.
+
+use super::escape;
+use crate::markup;
+
+struct Cue {
+    start: f64,
+    end: f64,
+    lines: Vec<String>,
+}
+
+fn parse_timing(line: &str) -> Option<(f64, f64)> {
+    let (start, rest) = line.split_once("-->")?;
+    // WebVTT cue settings follow the end time.
+    let end = rest.split_whitespace().next()?;
+    let start = markup::parse_timestamp(start)?;
+    let end = markup::parse_timestamp(end)?;
+    Some((start, end.max(start)))
+}
+
+/// Both formats are blocks separated by blank lines, each with an optional
+/// identifier, a timing line and the text. Blocks without a timing line,
+/// such as the WebVTT header and `NOTE` blocks, are skipped.
+fn cues(text: &str) -> Vec<Cue> {
+    let lines: Vec<&str> = text.trim_start_matches('\u{FEFF}').lines().collect();
+    let mut cues = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = lines[start..]
+            .iter()
+            .position(|line| line.trim().is_empty())
+            .map_or(lines.len(), |offset| start + offset);
+        let block = &lines[start..end];
+        let timing = block.iter().take(2).position(|line| line.contains("-->"));
+        if let Some((index, (cue_start, cue_end))) =
+            timing.and_then(|index| Some((index, parse_timing(block[index])?)))
+        {
+            cues.push(Cue {
+                start: cue_start,
+                end: cue_end,
+                lines: block[index + 1..]
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect(),
+            });
+        }
+        start = end + 1;
+    }
+    cues
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&lrm;", "")
+        .replace("&rlm;", "")
+        .replace("&amp;", "&")
+}
+
+/// Converts one line of cue text. Emphasis may span lines, so its nesting is
+/// carried in `emphasis`.
+fn cue_line(line: &str, emphasis: &mut usize) -> String {
+    let mut out = String::new();
+    let mut open = false;
+    let push_text = |out: &mut String, open: &mut bool, text: &str, emphasis: usize| {
+        let text = decode_entities(text);
+        if emphasis > 0 && !*open && !text.trim().is_empty() {
+            out.push_str("**");
+            *open = true;
+        }
+        out.push_str(&escape(&text));
+    };
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        let tag = rest.find('<').into_iter().chain(rest.find("{\\")).min();
+        let Some(tag_start) = tag else {
+            push_text(&mut out, &mut open, rest, *emphasis);
+            break;
+        };
+        push_text(&mut out, &mut open, &rest[..tag_start], *emphasis);
+        let after = &rest[tag_start..];
+        let close = if after.starts_with('<') { '>' } else { '}' };
+        let Some(tag_end) = after.find(close) else {
+            push_text(&mut out, &mut open, after, *emphasis);
+            break;
+        };
+        let tag = after[1..tag_end].trim();
+        let name = tag
+            .split(|c: char| c == '.' || c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match name.as_str() {
+            "i" | "b" => *emphasis += 1,
+            "/i" | "/b" => {
+                *emphasis = emphasis.saturating_sub(1);
+                if *emphasis == 0 && open {
+                    out.push_str("**");
+                    open = false;
+                }
+            }
+            "v" => {
+                let speaker = tag
+                    .split_once(char::is_whitespace)
+                    .map(|(_, name)| name.trim());
+                if let Some(speaker) = speaker.filter(|speaker| !speaker.is_empty()) {
+                    // Notes cannot sit inside emphasis; it reopens after.
+                    if open {
+                        out.push_str("**");
+                        open = false;
+                    }
+                    out.push_str(&format!("[note: {}]", escape(speaker)));
+                }
+            }
+            _ => {}
+        }
+        rest = &after[tag_end + 1..];
+    }
+    if open {
+        out.push_str("**");
+    }
+    out.trim().to_string()
+}
+
+fn format_time(seconds: f64) -> String {
+    let millis = (seconds * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Converts SRT or WebVTT subtitles to script markup.
+pub fn to_script(text: &str) -> Result<String, String> {
+    let cues = cues(text);
+    if cues.is_empty() {
+        return Err("no subtitle cues found".to_string());
+    }
+    let mut lines = Vec::new();
+    for cue in cues {
+        let mut emphasis = 0;
+        let text: Vec<String> = cue
+            .lines
+            .iter()
+            .map(|line| cue_line(line, &mut emphasis))
+            .filter(|line| !line.is_empty())
+            .collect();
+        let Some((first, rest)) = text.split_first() else {
+            continue;
+        };
+        lines.push(format!(
+            "[at {}-{}]{first}",
+            format_time(cue.start),
+            format_time(cue.end)
+        ));
+        lines.extend(rest.iter().cloned());
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timing;
+
+    #[test]
+    fn converts_srt() {
+        let srt = "\u{FEFF}1\r\n00:00:01,000 --> 00:00:03,500\r\n{\\an8}<i>大家好，\r\n欢迎收看</i> [片头]\r\n\r\n\
+                   2\r\n00:00:04,250 --> 00:00:06,000\r\n5 * 3 = 15\r\n\r\n\
+                   3\r\n00:00:07,000 --> 00:00:08,000\r\n<font color=\"#fff\"></font>\r\n";
+        let script = to_script(srt).unwrap();
+        assert_eq!(
+            script,
+            "[at 00:00:01.000-00:00:03.500]**大家好，**\n\
+             **欢迎收看** \\[片头\\]\n\
+             [at 00:00:04.250-00:00:06.000]5 \\* 3 = 15"
+        );
+        let parsed = markup::parse(&script);
+        assert!(parsed.errors.is_empty());
+        let estimate = timing::estimate_document(&parsed.document, 60.0);
+        assert!(estimate.timed);
+        assert_eq!(estimate.total_seconds, 6.0);
+    }
+
+    #[test]
+    fn converts_webvtt() {
+        let vtt = "WEBVTT - dubbing\n\n\
+                   NOTE reviewed by the director\n\n\
+                   intro\n01:02.500 --> 01:05.000 align:start line:90%\n<v.loud Anna>Tom &amp; <b>Jerry</b>&nbsp;!\n\n\
+                   00:01:06.000 --> 00:01:07.000\n<c.yellow>Bye</c>\n";
+        assert_eq!(
+            to_script(vtt).unwrap(),
+            "[at 00:01:02.500-00:01:05.000][note: Anna]Tom & **Jerry** !\n\
+             [at 00:01:06.000-00:01:07.000]Bye"
+        );
+        assert!(to_script("WEBVTT\n\nNOTE nothing here\n").is_err());
+    }
+}
@@ -0,0 +1,409 @@
+//! Local control socket used by the command-line client (`flash-prompter play`).
+//!
+//! The running app listens on a per-user local socket: a named pipe on
+//! Windows, and elsewhere a socket file in a directory only the user can
+//! open, so other users can neither connect nor claim the name first. The
+//! protocol is newline-delimited JSON, one request per line and
+//! one response line for each, in order. A connection may carry any number
+//! of requests.
+//!
+//! Every request carries the protocol version and a command:
+//!
+//! - `{"version": 1, "command": "status"}`
+//! - `{"version": 1, "command": "play"}`, also `pause`, `toggle`, `stop`
+//! - `{"version": 1, "command": "seek", "progress": 0.5}`
+//! - `{"version": 1, "command": "speed", "rate": 120}`
+//! - `{"version": 1, "command": "load", "path": "/abs/path/script.md"}`
+//!
+//! Responses are `{"version": 1, "ok": true, "status": {...}}` with the
+//! playback snapshot after the command, or `{"version": 1, "ok": false,
+//! "error": "..."}`. A request with a different version is answered with an
+//! error rather than guessed at; new commands and fields may be added within
+//! a version, so clients should ignore fields they do not know.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use interprocess::local_socket::prelude::*;
+#[cfg(unix)]
+use interprocess::local_socket::GenericFilePath;
+#[cfg(not(unix))]
+use interprocess::local_socket::GenericNamespaced;
+use interprocess::local_socket::{ConnectOptions, ListenerOptions, Name, Stream};
+use interprocess::ConnectWaitMode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::control::{AppControl, Control, Controller};
+use crate::launch;
+use crate::playback::PlaybackSnapshot;
+use crate::script_file::OpenedFile;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+const SOCKET_NAME: &str = "flash-prompter";
+/// Longer request lines are refused and the connection closed.
+const MAX_REQUEST: usize = 64 * 1024;
+/// How long the client waits to connect and for each response.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum Command {
+    Status,
+    Play,
+    Pause,
+    Toggle,
+    Stop,
+    Seek {
+        progress: f64,
+    },
+    Speed {
+        rate: f64,
+    },
+    /// Loads a script file. The path should be absolute, since the app does
+    /// not share the client's working directory.
+    Load {
+        path: PathBuf,
+    },
+}
+
+impl Command {
+    /// The playback control this command maps to, if any.
+    pub fn control(&self) -> Option<Control> {
+        match self {
+            Command::Play => Some(Control::Play),
+            Command::Pause => Some(Control::Pause),
+            Command::Toggle => Some(Control::Toggle),
+            Command::Stop => Some(Control::Stop),
+            Command::Seek { progress } => Some(Control::Seek {
+                progress: *progress,
+            }),
+            Command::Speed { rate } => Some(Control::Speed { rate: *rate }),
+            Command::Status | Command::Load { .. } => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub version: u32,
+    #[serde(flatten)]
+    pub command: Command,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub version: u32,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Response {
+    fn from_result(result: Result<PlaybackSnapshot, String>) -> Self {
+        let result =
+            result.and_then(|snapshot| serde_json::to_value(snapshot).map_err(|e| e.to_string()));
+        match result {
+            Ok(status) => Self {
+                version: PROTOCOL_VERSION,
+                ok: true,
+                status: Some(status),
+                error: None,
+            },
+            Err(error) => Self {
+                version: PROTOCOL_VERSION,
+                ok: false,
+                status: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+/// Runs one command in the app.
+pub type Handler = Arc<dyn Fn(Command) -> Result<PlaybackSnapshot, String> + Send + Sync>;
+
+/// The socket name for the current user.
+pub fn socket_name() -> io::Result<Name<'static>> {
+    let user = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    named(&format!("{SOCKET_NAME}-{user}"))
+}
+
+/// `$XDG_RUNTIME_DIR`, or else a directory of the user's own in the temp
+/// dir. Either must be owned by the user and closed to everyone else.
+#[cfg(unix)]
+fn private_dir() -> io::Result<PathBuf> {
+    use std::fs::{self, DirBuilder};
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    // SAFETY: `geteuid` has no preconditions and cannot fail.
+    let uid = unsafe { libc::geteuid() };
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => std::env::temp_dir().join(format!("{SOCKET_NAME}-{uid}")),
+    };
+    match DirBuilder::new().mode(0o700).create(&dir) {
+        Err(err) if err.kind() != io::ErrorKind::AlreadyExists => return Err(err),
+        _ => {}
+    }
+    // Not followed if it is a symlink, which someone else could have placed.
+    let metadata = fs::symlink_metadata(&dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a private directory", dir.display()),
+        ));
+    }
+    Ok(dir)
+}
+
+#[cfg(unix)]
+fn named(name: &str) -> io::Result<Name<'static>> {
+    private_dir()?
+        .join(format!("{name}.sock"))
+        .to_fs_name::<GenericFilePath>()
+}
+
+/// By default only the user who created a named pipe and administrators may
+/// write to it.
+#[cfg(not(unix))]
+fn named(name: &str) -> io::Result<Name<'static>> {
+    format!("{name}.sock").to_ns_name::<GenericNamespaced>()
+}
+
+fn respond(handler: &Handler, line: &str) -> Response {
+    let request = match serde_json::from_str::<Request>(line) {
+        Ok(request) => request,
+        Err(err) => {
+            // Report a version mismatch over a parse error when possible.
+            let version = serde_json::from_str::<Value>(line)
+                .ok()
+                .and_then(|value| value.get("version")?.as_u64());
+            return Response::from_result(Err(match version {
+                Some(version) if version != PROTOCOL_VERSION as u64 => {
+                    format!("unsupported protocol version {version}, expected {PROTOCOL_VERSION}")
+                }
+                _ => format!("invalid request: {err}"),
+            }));
+        }
+    };
+    if request.version != PROTOCOL_VERSION {
+        return Response::from_result(Err(format!(
+            "unsupported protocol version {}, expected {PROTOCOL_VERSION}",
+            request.version
+        )));
+    }
+    Response::from_result(handler(request.command))
+}
+
+fn serve_connection(handler: &Handler, stream: Stream) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = (&mut stream)
+            .take(MAX_REQUEST as u64 + 1)
+            .read_line(&mut line)?;
+        if read == 0 {
+            return Ok(());
+        }
+        let too_long = line.len() > MAX_REQUEST;
+        let response = if too_long {
+            Response::from_result(Err(format!("request is longer than {MAX_REQUEST} bytes")))
+        } else if line.trim().is_empty() {
+            continue;
+        } else {
+            respond(handler, line.trim())
+        };
+        let mut json = serde_json::to_string(&response)?;
+        json.push('\n');
+        stream.get_mut().write_all(json.as_bytes())?;
+        if too_long {
+            return Ok(());
+        }
+    }
+}
+
+/// Listens on `name` on a background thread, one thread per connection.
+pub fn serve(name: Name<'static>, handler: Handler) -> io::Result<()> {
+    // Only one instance runs, so a leftover socket file is stale.
+    let options = ListenerOptions::new().name(name).try_overwrite(true);
+    // Other Unix systems do not support a mode on sockets; the private
+    // directory is what keeps other users out there.
+    #[cfg(target_os = "linux")]
+    let options = {
+        use interprocess::os::unix::local_socket::ListenerOptionsExt;
+        options.mode(0o600)
+    };
+    let listener = options.create_sync()?;
+    thread::spawn(move || {
+        for stream in listener.incoming().filter_map(Result::ok) {
+            let handler = handler.clone();
+            thread::spawn(move || {
+                let _ = serve_connection(&handler, stream);
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Runs `command` through `control`. A loaded file is passed to `show`.
+fn dispatch(
+    control: &AppControl,
+    command: Command,
+    show: impl FnOnce(OpenedFile),
+) -> Result<PlaybackSnapshot, String> {
+    if let Some(action) = command.control() {
+        return control.apply(action);
+    }
+    match command {
+        Command::Load { path } => {
+            let (file, snapshot) = launch::load_file(control, &path)?;
+            show(file);
+            Ok(snapshot)
+        }
+        _ => Ok(control.status()),
+    }
+}
+
+/// Starts the app's control socket. Playback commands go through the same
+/// `AppControl` as the UI, and `load` takes the same path as a file opened
+/// from the OS, which also shows it in the editor.
+pub fn start(app: &AppHandle) -> io::Result<()> {
+    let app = app.clone();
+    let handler: Handler = Arc::new(move |command| {
+        let control = app.state::<Arc<AppControl>>();
+        dispatch(&control, command, |file| {
+            launch::show_loaded(&app, &control, file);
+        })
+    });
+    serve(socket_name()?, handler)
+}
+
+/// Sends one request to the running app and waits for the response, for at
+/// most [`CLIENT_TIMEOUT`] at each step.
+pub fn request(name: Name<'_>, command: Command) -> io::Result<Response> {
+    let stream = ConnectOptions::new()
+        .name(name)
+        .wait_mode(ConnectWaitMode::Timeout(CLIENT_TIMEOUT))
+        .connect_sync()?;
+    stream.set_recv_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_send_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut stream = BufReader::new(stream);
+    let mut json = serde_json::to_string(&Request {
+        version: PROTOCOL_VERSION,
+        command,
+    })?;
+    json.push('\n');
+    stream.get_mut().write_all(json.as_bytes())?;
+    let mut line = String::new();
+    stream.read_line(&mut line)?;
+    if line.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the app closed the connection",
+        ));
+    }
+    serde_json::from_str(&line).map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::playback::{Playback, PlaybackState, SystemClock};
+
+    #[test]
+    fn requests_carry_the_version_and_command() {
+        let request = Request {
+            version: PROTOCOL_VERSION,
+            command: Command::Seek { progress: 0.5 },
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({ "version": 1, "command": "seek", "progress": 0.5 })
+        );
+    }
+
+    #[test]
+    fn serves_commands_over_the_socket() {
+        let dir = std::env::temp_dir().join(format!("flash-prompter-ipc-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("script.txt");
+        fs::write(&script, "第一行\n第二行").unwrap();
+        let playback = Playback::new(Arc::new(SystemClock::new()));
+        let config = Config::load(dir.join("settings.json"), None);
+        let control = Arc::new(AppControl::new(playback.clone(), config));
+        let shown = Arc::new(Mutex::new(Vec::new()));
+        let target = shown.clone();
+        let handler: Handler = Arc::new(move |command| {
+            dispatch(&control, command, |file| target.lock().unwrap().push(file))
+        });
+        let name = format!("flash-prompter-test-{}", std::process::id());
+        serve(named(&name).unwrap(), handler).unwrap();
+
+        let load = Command::Load {
+            path: script.clone(),
+        };
+        let response = request(named(&name).unwrap(), load).unwrap();
+        assert!(response.ok, "{:?}", response.error);
+        assert_eq!(response.status.unwrap()["lineText"], "第一行");
+        assert_eq!(shown.lock().unwrap()[0].text, "第一行\n第二行");
+        request(named(&name).unwrap(), Command::Play).unwrap();
+        assert_eq!(playback.snapshot().state, PlaybackState::Playing);
+        // The editor syncing to the shown file keeps it playing.
+        assert_eq!(
+            playback.sync("第一行\n第二行").state,
+            PlaybackState::Playing
+        );
+
+        let response = request(named(&name).unwrap(), Command::Seek { progress: 0.5 }).unwrap();
+        assert!(response.ok);
+        assert_eq!(response.status.unwrap()["progress"], 0.5);
+        let missing = Command::Load {
+            path: dir.join("missing.txt"),
+        };
+        let response = request(named(&name).unwrap(), missing).unwrap();
+        assert!(!response.ok);
+        assert_eq!(shown.lock().unwrap().len(), 1);
+
+        let stream = Stream::connect(named(&name).unwrap()).unwrap();
+        let mut stream = BufReader::new(stream);
+        stream
+            .get_mut()
+            .write_all(b"{\"version\":2,\"command\":\"play\"}\n")
+            .unwrap();
+        let mut line = String::new();
+        stream.read_line(&mut line).unwrap();
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert!(!response.ok);
+        assert!(response.error.unwrap().contains("version 2"));
+
+        // An endless line is cut off instead of read into memory.
+        let stream = Stream::connect(named(&name).unwrap()).unwrap();
+        let mut stream = BufReader::new(stream);
+        stream
+            .get_mut()
+            .write_all(&vec![b' '; MAX_REQUEST + 1])
+            .unwrap();
+        let mut line = String::new();
+        stream.read_line(&mut line).unwrap();
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert!(response.error.unwrap().contains("longer than"));
+        line.clear();
+        assert_eq!(stream.read_line(&mut line).unwrap(), 0);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,208 @@
+//! How the app was started and what it was asked to do.
+//!
+//! Launches at login carry `AUTOSTART_ARG`, which is registered with the
+//! autostart entry, so they can follow the `autostartLaunch` settings instead
+//! of behaving like a manual launch. Only one instance runs: a second launch
+//! hands its arguments to the running one through the single-instance plugin
+//! and exits, and the running instance comes to the front and acts on them.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::config::AutostartLaunch;
+use crate::control::{AppControl, Control, Controller};
+use crate::deep_link;
+use crate::playback::{self, PlaybackSnapshot};
+use crate::script_file::{self, OpenedFile};
+
+pub const AUTOSTART_ARG: &str = "--autostart";
+/// Asks the editor to show a file opened from the command line or the OS.
+pub const OPEN_FILE_EVENT: &str = "launch://open-file";
+
+/// Command-line arguments, without the program name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LaunchArgs {
+    pub autostart: bool,
+    /// Script files, resolved against the launching process's directory.
+    pub files: Vec<PathBuf>,
+    pub controls: Vec<Control>,
+    /// `flashprompter://` links, passed by the OS on Windows and Linux.
+    pub links: Vec<String>,
+}
+
+impl LaunchArgs {
+    /// Unknown flags are ignored so older instances tolerate newer launchers.
+    pub fn parse<I, S>(args: I, cwd: &Path) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut parsed = LaunchArgs::default();
+        for arg in args {
+            match arg.as_ref() {
+                AUTOSTART_ARG => parsed.autostart = true,
+                "--play" => parsed.controls.push(Control::Play),
+                "--pause" => parsed.controls.push(Control::Pause),
+                "--toggle" => parsed.controls.push(Control::Toggle),
+                "--stop" => parsed.controls.push(Control::Stop),
+                link if deep_link::is_link(link) => parsed.links.push(link.to_string()),
+                flag if flag.starts_with('-') => {}
+                path => parsed.files.push(cwd.join(path)),
+            }
+        }
+        parsed
+    }
+}
+
+/// Decided once at startup and handed to the view on request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchInfo {
+    pub autostart: bool,
+    pub start_hidden: bool,
+    pub restore_last_script: bool,
+    pub skip_update_check: bool,
+    /// File passed on the command line, shown instead of the last script.
+    pub file: Option<OpenedFile>,
+}
+
+impl LaunchInfo {
+    pub fn new(args: &LaunchArgs, options: AutostartLaunch) -> Self {
+        let autostart = args.autostart;
+        let file = args
+            .files
+            .first()
+            .and_then(|path| script_file::read(path).ok());
+        Self {
+            autostart,
+            start_hidden: autostart && options.start_hidden && file.is_none(),
+            restore_last_script: autostart && options.restore_last_script && file.is_none(),
+            skip_update_check: autostart && options.skip_update_check,
+            file,
+        }
+    }
+}
+
+/// Runs the playback controls and links passed on the command line.
+pub fn apply_controls(app: &AppHandle, args: &LaunchArgs) {
+    let control = app.state::<Arc<AppControl>>();
+    for action in &args.controls {
+        let _ = control.apply(action.clone());
+    }
+    for link in &args.links {
+        deep_link::handle(app, link);
+    }
+}
+
+/// Callback for the single-instance plugin, run in the first instance with
+/// the arguments of a later launch.
+pub fn handle_second_instance(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let args = LaunchArgs::parse(argv.iter().skip(1), Path::new(&cwd));
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    if let Some(path) = args.files.first() {
+        let _ = open_file(app, path);
+    }
+    apply_controls(app, &args);
+}
+
+/// Reads `path`, loads it for playback and hands it to the editor. Used for
+/// files opened while the app is running, from a second launch, the OS
+/// (macOS "Open With") or the control socket.
+pub fn open_file(app: &AppHandle, path: &Path) -> Result<PlaybackSnapshot, String> {
+    let control = app.state::<Arc<AppControl>>();
+    let (file, snapshot) = load_file(&control, path)?;
+    show_loaded(app, &control, file);
+    Ok(snapshot)
+}
+
+/// Reads `path` and loads it for playback, so a control that follows acts
+/// on it even before the editor has shown it.
+pub fn load_file(
+    control: &AppControl,
+    path: &Path,
+) -> Result<(OpenedFile, PlaybackSnapshot), String> {
+    let file = script_file::read(path)?;
+    let snapshot = control.load(&file.text);
+    Ok((file, snapshot))
+}
+
+/// Tells the views about a file [`load_file`] loaded and shows it in the
+/// editor, which then syncs to the same text and leaves playback as is.
+pub fn show_loaded(app: &AppHandle, control: &AppControl, file: OpenedFile) {
+    let _ = app.emit(playback::DOCUMENT_EVENT, control.document());
+    show_file(app, file);
+}
+
+/// Hands a file to the editor of the main window and brings it forward.
+pub fn show_file(app: &AppHandle, file: OpenedFile) {
+    let _ = app.emit_to("main", OPEN_FILE_EVENT, file);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+#[tauri::command]
+pub fn launch_info(info: State<'_, LaunchInfo>) -> LaunchInfo {
+    info.inner().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_autostart_launches_follow_the_options() {
+        let options = AutostartLaunch {
+            start_hidden: true,
+            restore_last_script: true,
+            skip_update_check: false,
+        };
+        let cwd = Path::new("/");
+        let manual = LaunchInfo::new(&LaunchArgs::parse(Vec::<String>::new(), cwd), options);
+        assert_eq!(manual, LaunchInfo::default());
+
+        let login = LaunchInfo::new(&LaunchArgs::parse([AUTOSTART_ARG], cwd), options);
+        assert_eq!(
+            login,
+            LaunchInfo {
+                autostart: true,
+                start_hidden: true,
+                restore_last_script: true,
+                skip_update_check: false,
+                file: None,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_files_relative_to_the_launch_directory() {
+        let cwd = Path::new("host");
+        let absolute = std::env::temp_dir().join("outro.txt");
+        let args = LaunchArgs::parse(
+            [
+                "notes/intro.txt",
+                "--toggle",
+                "--unknown",
+                absolute.to_str().unwrap(),
+            ],
+            cwd,
+        );
+        assert_eq!(
+            args,
+            LaunchArgs {
+                autostart: false,
+                files: vec![cwd.join("notes/intro.txt"), absolute],
+                controls: vec![Control::Toggle],
+                links: Vec::new(),
+            }
+        );
+    }
+}
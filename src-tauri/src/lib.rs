@@ -1,61 +1,247 @@
-use tauri::{LogicalPosition, LogicalSize, Manager};
-
-#[cfg(target_os = "windows")]
-use raw_window_handle::{HasWindowHandle, RawWindowHandle};
-#[cfg(target_os = "windows")]
-use windows::Win32::Foundation::HWND;
-#[cfg(target_os = "windows")]
-use windows::Win32::UI::WindowsAndMessaging::{
-    SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE,
-};
-
-#[cfg(target_os = "windows")]
-fn exclude_from_capture(window: &tauri::WebviewWindow) {
-    if let Ok(handle) = window.window_handle() {
-        if let RawWindowHandle::Win32(win) = handle.as_raw() {
-            let hwnd = HWND(win.hwnd.get() as _);
-            let _ = unsafe { SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE) };
-        }
+mod capture;
+pub mod cli;
+mod config;
+mod control;
+mod deep_link;
+mod hotkeys;
+mod import;
+mod ipc;
+mod launch;
+mod markup;
+mod monitors;
+mod output;
+mod overlay;
+mod placement;
+mod playback;
+mod profiles;
+mod remote;
+mod script_file;
+mod scripts;
+mod timing;
+mod tray;
+mod window_state;
+
+use std::sync::Arc;
+
+use tauri::{Emitter, Manager};
+use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+/// Registers or removes the login entry to match `enabled`. With `rewrite`
+/// an existing entry is registered again, so entries made before they
+/// carried the autostart flag pick it up.
+fn sync_autostart(app: &tauri::AppHandle, enabled: bool, rewrite: bool) {
+    let autolaunch = app.autolaunch();
+    let registered = autolaunch.is_enabled().unwrap_or(!enabled);
+    if registered != enabled || (enabled && rewrite) {
+        let _ = if enabled {
+            autolaunch.enable()
+        } else {
+            autolaunch.disable()
+        };
+    }
+}
+
+/// Closes the output window and saves the main window's place. Runs when
+/// the main window closes and before quitting from the tray, which skips
+/// the window events.
+fn before_exit(app: &tauri::AppHandle) {
+    let _ = output::close(app);
+    if let Some(window_state) = app.try_state::<window_state::WindowState>() {
+        let _ = window_state.save();
     }
 }
 
-#[cfg(not(target_os = "windows"))]
-fn exclude_from_capture(_window: &tauri::WebviewWindow) {}
+/// Reports a problem found while starting up, on stderr and in a dialog,
+/// since the app keeps no log file.
+fn warn(app: &tauri::AppHandle, message: &str) {
+    eprintln!("{message}");
+    app.dialog()
+        .message(message)
+        .title("Flash Prompter")
+        .kind(MessageDialogKind::Warning)
+        .show(|_| {});
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(
+            launch::handle_second_instance,
+        ))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            None,
+            Some(vec![launch::AUTOSTART_ARG]),
         ))
         .setup(|app| {
+            let scripts_dir = app.path().app_data_dir()?.join("scripts");
+            app.manage(scripts::ScriptLibrary::new(scripts_dir));
+
+            let playback = playback::Playback::new(Arc::new(playback::SystemClock::new()));
+            let handle = app.handle().clone();
+            playback.subscribe(move |snapshot| {
+                let _ = handle.emit(playback::POSITION_EVENT, snapshot);
+            });
+            playback.spawn_ticker(playback::TICK);
+
+            let settings_path = app.path().app_config_dir()?.join("settings.json");
+            let config = config::Config::load(settings_path, config::defaults_path().as_deref());
+            if let Some(warning) = config.load_warning() {
+                warn(app.handle(), warning);
+            }
+            playback.set_rate(config.get().tuning.words_per_minute as f64);
+            sync_autostart(app.handle(), config.get().auto_start, true);
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let args = launch::LaunchArgs::parse(std::env::args().skip(1), &cwd);
+            let launch = launch::LaunchInfo::new(&args, config.get().autostart_launch);
+            let start_hidden = launch.start_hidden;
+            app.manage(launch);
+            let handle = app.handle().clone();
+            let rate_target = playback.clone();
+            let overlay_sync = overlay::OverlaySync::new(config.get().overlay);
+            config.subscribe(move |settings| {
+                rate_target.set_rate(settings.tuning.words_per_minute as f64);
+                sync_autostart(&handle, settings.auto_start, false);
+                overlay_sync.update(&handle, settings.overlay);
+                tray::refresh(&handle);
+                let _ = handle.emit(config::SETTINGS_EVENT, settings);
+            });
+            let control = Arc::new(control::AppControl::new(playback.clone(), config.clone()));
+            app.manage(config);
+
+            let remote_path = app.path().app_config_dir()?.join("remote.json");
+            let remote = remote::RemoteServer::load(remote_path, control.clone());
+            let _ = remote.apply();
+            app.manage(remote);
+            app.manage(control);
+            app.manage(playback);
+
+            let hotkeys_path = app.path().app_config_dir()?.join("hotkeys.json");
+            app.manage(hotkeys::Hotkeys::load(hotkeys_path));
+            app.handle().plugin(
+                tauri_plugin_global_shortcut::Builder::new()
+                    .with_handler(hotkeys::handle_shortcut)
+                    .build(),
+            )?;
+            app.state::<hotkeys::Hotkeys>().register_all(app.handle());
+
+            app.manage(capture::CaptureState::default());
+            let window_state_path = app.path().app_config_dir()?.join("window-state.json");
+            app.manage(window_state::WindowState::load(window_state_path));
+            tray::build(app.handle())?;
             if let Some(window) = app.get_webview_window("main") {
-                exclude_from_capture(&window);
-                let window_width = 420.0;
-                let window_height = 300.0;
-                let top_offset = -300.0;
-                if let Ok(Some(monitor)) = app.primary_monitor() {
-                    let scale_factor = monitor.scale_factor();
-                    let monitor_size = monitor.size();
-                    let monitor_position = monitor.position();
-                    let logical_width = monitor_size.width as f64 / scale_factor;
-                    let logical_height = monitor_size.height as f64 / scale_factor;
-                    let logical_x = monitor_position.x as f64 / scale_factor;
-                    let logical_y = monitor_position.y as f64 / scale_factor;
-                    let center_x = logical_x + (logical_width - window_width) / 2.0;
-                    let center_y =
-                        logical_y + (logical_height - window_height) / 2.0 + top_offset;
-                    let _ = window.set_size(LogicalSize::new(window_width, window_height));
-                    let _ = window.set_position(LogicalPosition::new(center_x, center_y));
-                    let _ = window.set_always_on_top(true);
+                capture::apply(app.handle(), &window);
+                monitors::position_main_window(app.handle());
+                let _ = window.set_always_on_top(true);
+                overlay::apply(&window, app.state::<config::Config>().get().overlay);
+                if !start_hidden {
+                    let _ = window.show();
+                }
+            }
+            monitors::spawn_watcher(app.handle().clone());
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    deep_link::handle(&handle, url.as_str());
                 }
+            });
+            #[cfg(any(target_os = "windows", target_os = "linux"))]
+            let _ = app.deep_link().register_all();
+            if let Err(err) = ipc::start(app.handle()) {
+                warn(
+                    app.handle(),
+                    &format!("Command-line control is unavailable: {err}"),
+                );
             }
+            launch::apply_controls(app.handle(), &args);
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if window.label() != "main" {
+                return;
+            }
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                before_exit(window.app_handle());
+                return;
+            }
+            let Some(window_state) = window.try_state::<window_state::WindowState>() else {
+                return;
+            };
+            match event {
+                tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                    window_state.record(window);
+                }
+                tauri::WindowEvent::Focused(false) => {
+                    let _ = window_state.save();
+                }
+                _ => {}
+            }
+        })
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(tauri::generate_handler![
+            capture::capture_status,
+            capture::set_capture_exclusion,
+            config::get_settings,
+            config::update_settings,
+            config::import_legacy_settings,
+            config::reset_settings,
+            monitors::list_monitors,
+            monitors::set_target_monitor,
+            placement::apply_placement,
+            profiles::switch_profile,
+            profiles::save_profile,
+            profiles::delete_profile,
+            profiles::export_profiles,
+            profiles::import_profiles,
+            hotkeys::get_hotkeys,
+            hotkeys::set_hotkey,
+            hotkeys::reset_hotkeys,
+            import::markdown::import_markdown,
+            launch::launch_info,
+            markup::parse_script,
+            output::open_output_window,
+            output::close_output_window,
+            output::set_output_monitor,
+            playback::playback_load,
+            playback::playback_document,
+            playback::playback_play,
+            playback::playback_pause,
+            playback::playback_toggle,
+            playback::playback_stop,
+            playback::playback_seek,
+            playback::playback_set_rate,
+            playback::playback_status,
+            remote::remote_status,
+            remote::update_remote,
+            remote::remote_pairing,
+            remote::regenerate_remote_token,
+            script_file::open_script_file,
+            scripts::list_scripts,
+            scripts::load_script,
+            scripts::save_script,
+            scripts::rename_script,
+            scripts::duplicate_script,
+            scripts::delete_script,
+            timing::estimate_timing
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, _event| {
+            // macOS delivers files opened from Finder as an event, not as arguments.
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = _event {
+                for path in urls
+                    .iter()
+                    .filter_map(|url| url.to_file_path().ok())
+                    .take(1)
+                {
+                    let _ = launch::open_file(_app, &path);
+                }
+            }
+        });
 }
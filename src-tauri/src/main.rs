@@ -1,5 +1,8 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 
 fn main() {
+    if let Some(code) = flash_prompter::cli::run_from_args() {
+        std::process::exit(code);
+    }
     flash_prompter::run();
 }
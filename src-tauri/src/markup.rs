@@ -0,0 +1,467 @@
+//! Inline cue markup for scripts.
+//!
+//! - `[pause 2s]` / `[pause 500ms]` holds the scroll for a fixed time.
+//! - `[speed 0.8]` scales the reading rate until the next speed cue.
+//! - `**text**` marks emphasis.
+//! - `[note: smile]` is an operator note that is never read aloud.
+//! - `[section Intro]` marks the start of a section, e.g. an imported
+//!   heading. Like notes, it is shown to the operator and not read.
+//! - `[at 1:02.5]` / `[at 00:01:02.500-00:01:05.000]` pins the line to a
+//!   time in an existing edit, e.g. a subtitle cue. Scripts with these cues
+//!   play back on their times instead of the reading rate.
+//!
+//! A backslash escapes `[`, `]`, `*` and `\`. Malformed markup is reported
+//! with its position and kept in the document as plain text.
+
+use serde::Serialize;
+
+const MIN_SPEED: f64 = 0.25;
+const MAX_SPEED: f64 = 4.0;
+const MAX_PAUSE_SECONDS: f64 = 60.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Inline {
+    Text { text: String },
+    Emphasis { text: String },
+    Pause { seconds: f64 },
+    Speed { factor: f64 },
+    Note { text: String },
+    Section { title: String },
+    Time { start: f64, end: Option<f64> },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Line {
+    pub inlines: Vec<Inline>,
+}
+
+impl Line {
+    /// The text that is read aloud, without cues or notes.
+    pub fn plain_text(&self) -> String {
+        self.inlines
+            .iter()
+            .filter_map(|inline| match inline {
+                Inline::Text { text } | Inline::Emphasis { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Document {
+    pub lines: Vec<Line>,
+}
+
+/// Position of malformed markup. `line` and `column` are 1-based and
+/// `column`/`length` count characters.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ParsedScript {
+    pub document: Document,
+    pub errors: Vec<ParseError>,
+}
+
+pub fn parse(text: &str) -> ParsedScript {
+    let mut parsed = ParsedScript::default();
+    for (index, line) in text.lines().enumerate() {
+        let line = LineParser::new(line, index + 1, &mut parsed.errors).parse();
+        parsed.document.lines.push(line);
+    }
+    parsed
+}
+
+struct LineParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    line_number: usize,
+    line: Line,
+    text: String,
+    errors: &'a mut Vec<ParseError>,
+}
+
+impl<'a> LineParser<'a> {
+    fn new(source: &str, line_number: usize, errors: &'a mut Vec<ParseError>) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            pos: 0,
+            line_number,
+            line: Line::default(),
+            text: String::new(),
+            errors,
+        }
+    }
+
+    fn parse(mut self) -> Line {
+        while self.pos < self.chars.len() {
+            match self.chars[self.pos] {
+                '\\' if self.peek(1).is_some_and(is_escapable) => {
+                    self.text.push(self.chars[self.pos + 1]);
+                    self.pos += 2;
+                }
+                '*' if self.peek(1) == Some('*') => self.emphasis(),
+                '[' => self.cue(),
+                c => {
+                    self.text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        self.flush_text();
+        self.line
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn emphasis(&mut self) {
+        let start = self.pos;
+        let Some((content, end)) = self.scan_until(start + 2, |chars, i| {
+            chars[i] == '*' && chars.get(i + 1) == Some(&'*')
+        }) else {
+            self.error(start, 2, "unclosed emphasis, expected closing `**`");
+            self.text.push_str("**");
+            self.pos += 2;
+            return;
+        };
+        self.push(Inline::Emphasis { text: content });
+        self.pos = end + 2;
+    }
+
+    fn cue(&mut self) {
+        let start = self.pos;
+        let Some((content, end)) = self.scan_until(start + 1, |chars, i| chars[i] == ']') else {
+            self.error(start, 1, "unclosed cue, expected `]`");
+            self.text.push('[');
+            self.pos += 1;
+            return;
+        };
+        let length = end + 1 - start;
+        match parse_cue(&content) {
+            Ok(inline) => self.push(inline),
+            Err(message) => {
+                self.error(start, length, &message);
+                self.text.extend(&self.chars[start..=end]);
+            }
+        }
+        self.pos = end + 1;
+    }
+
+    /// Collects unescaped text from `from` up to the first position where
+    /// `is_end` matches, returning the text and that position.
+    fn scan_until(
+        &self,
+        from: usize,
+        is_end: impl Fn(&[char], usize) -> bool,
+    ) -> Option<(String, usize)> {
+        let mut content = String::new();
+        let mut i = from;
+        while i < self.chars.len() {
+            let c = self.chars[i];
+            if c == '\\' && self.chars.get(i + 1).copied().is_some_and(is_escapable) {
+                content.push(self.chars[i + 1]);
+                i += 2;
+                continue;
+            }
+            if is_end(&self.chars, i) {
+                return Some((content, i));
+            }
+            content.push(c);
+            i += 1;
+        }
+        None
+    }
+
+    fn push(&mut self, inline: Inline) {
+        self.flush_text();
+        self.line.inlines.push(inline);
+    }
+
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            let text = std::mem::take(&mut self.text);
+            self.line.inlines.push(Inline::Text { text });
+        }
+    }
+
+    fn error(&mut self, start: usize, length: usize, message: &str) {
+        self.errors.push(ParseError {
+            line: self.line_number,
+            column: start + 1,
+            length,
+            message: message.to_string(),
+        });
+    }
+}
+
+fn is_escapable(c: char) -> bool {
+    matches!(c, '[' | ']' | '*' | '\\')
+}
+
+fn parse_cue(content: &str) -> Result<Inline, String> {
+    let content = content.trim();
+    if let Some(rest) = content.strip_prefix("note") {
+        if rest.is_empty() || rest.starts_with([':', ' ']) {
+            let text = rest.trim_start_matches(':').trim();
+            if text.is_empty() {
+                return Err("note is empty".to_string());
+            }
+            return Ok(Inline::Note {
+                text: text.to_string(),
+            });
+        }
+    }
+    if let Some(rest) = content.strip_prefix("section") {
+        if rest.is_empty() || rest.starts_with([':', ' ']) {
+            let title = rest.trim_start_matches(':').trim();
+            if title.is_empty() {
+                return Err("section needs a title".to_string());
+            }
+            return Ok(Inline::Section {
+                title: title.to_string(),
+            });
+        }
+    }
+    if let Some(rest) = content.strip_prefix("at ") {
+        let (start, end) = match rest.split_once('-') {
+            Some((start, end)) => (start, Some(end)),
+            None => (rest, None),
+        };
+        let parse = |value: &str| {
+            parse_timestamp(value).ok_or_else(|| format!("invalid time `{}`", value.trim()))
+        };
+        let start = parse(start)?;
+        let end = end.map(parse).transpose()?;
+        if end.is_some_and(|end| end < start) {
+            return Err("cue ends before it starts".to_string());
+        }
+        return Ok(Inline::Time { start, end });
+    }
+    let mut parts = content.split_whitespace();
+    let name = parts.next().unwrap_or_default();
+    let argument = parts.next();
+    if parts.next().is_some() {
+        return Err(format!("too many arguments for `{name}`"));
+    }
+    match name {
+        "pause" => {
+            let argument = argument.ok_or("pause needs a duration, e.g. `[pause 2s]`")?;
+            let seconds = parse_duration(argument)
+                .ok_or_else(|| format!("invalid pause duration `{argument}`"))?;
+            if seconds <= 0.0 {
+                return Err("pause must be longer than 0s".to_string());
+            }
+            if seconds > MAX_PAUSE_SECONDS {
+                return Err(format!("pause must be at most {MAX_PAUSE_SECONDS}s"));
+            }
+            Ok(Inline::Pause { seconds })
+        }
+        "speed" => {
+            let argument = argument.ok_or("speed needs a factor, e.g. `[speed 0.8]`")?;
+            let factor: f64 = argument
+                .trim_end_matches('x')
+                .parse()
+                .map_err(|_| format!("invalid speed factor `{argument}`"))?;
+            if !(MIN_SPEED..=MAX_SPEED).contains(&factor) {
+                return Err(format!("speed must be between {MIN_SPEED} and {MAX_SPEED}"));
+            }
+            Ok(Inline::Speed { factor })
+        }
+        "at" => Err("at needs a time, e.g. `[at 1:02.5]`".to_string()),
+        "" => Err("empty cue".to_string()),
+        other => Err(format!("unknown cue `{other}`")),
+    }
+}
+
+fn parse_duration(value: &str) -> Option<f64> {
+    let (number, scale) = if let Some(ms) = value.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(s) = value.strip_suffix('s') {
+        (s, 1.0)
+    } else {
+        (value, 1.0)
+    };
+    let number: f64 = number.parse().ok()?;
+    number.is_finite().then_some(number * scale)
+}
+
+/// Parses `[[h:]m:]s[.fff]` in seconds. A comma may stand for the decimal
+/// point, as in SRT files.
+pub fn parse_timestamp(value: &str) -> Option<f64> {
+    let value = value.trim().replace(',', ".");
+    if !value.contains(':') {
+        return parse_duration(&value).filter(|seconds| *seconds >= 0.0);
+    }
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    let mut seconds = 0.0;
+    for part in parts {
+        let number: f64 = part.parse().ok()?;
+        if !number.is_finite() || number < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + number;
+    }
+    Some(seconds)
+}
+
+#[tauri::command]
+pub fn parse_script(text: String) -> ParsedScript {
+    parse(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Inline {
+        Inline::Text {
+            text: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_cues_and_emphasis() {
+        let parsed = parse("大家好[pause 1.5s]欢迎**Flash Prompter**[speed 0.8x][note: smile]");
+        assert!(parsed.errors.is_empty());
+        assert_eq!(
+            parsed.document.lines[0].inlines,
+            vec![
+                text("大家好"),
+                Inline::Pause { seconds: 1.5 },
+                text("欢迎"),
+                Inline::Emphasis {
+                    text: "Flash Prompter".to_string()
+                },
+                Inline::Speed { factor: 0.8 },
+                Inline::Note {
+                    text: "smile".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_sections() {
+        let parsed = parse("[section 开场 \\[v2\\]]\n[section]");
+        assert_eq!(
+            parsed.document.lines[0].inlines,
+            vec![Inline::Section {
+                title: "开场 [v2]".to_string()
+            }]
+        );
+        assert_eq!(parsed.errors[0].message, "section needs a title");
+    }
+
+    #[test]
+    fn parses_time_cues() {
+        let parsed = parse("[at 1:02.5]Hi\n[at 00:00:01,000-00:00:03.500]\n[at 4-2][at soon]");
+        assert_eq!(
+            parsed.document.lines[0].inlines[0],
+            Inline::Time {
+                start: 62.5,
+                end: None
+            }
+        );
+        assert_eq!(
+            parsed.document.lines[1].inlines[0],
+            Inline::Time {
+                start: 1.0,
+                end: Some(3.5)
+            }
+        );
+        let messages: Vec<_> = parsed.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec!["cue ends before it starts", "invalid time `soon`"]
+        );
+    }
+
+    #[test]
+    fn pause_units() {
+        let parsed = parse("[pause 500ms][pause 2]");
+        assert_eq!(
+            parsed.document.lines[0].inlines,
+            vec![
+                Inline::Pause { seconds: 0.5 },
+                Inline::Pause { seconds: 2.0 }
+            ]
+        );
+    }
+
+    #[test]
+    fn pause_must_be_positive_and_short() {
+        let parsed = parse("[pause -1][pause 0ms][pause 61s][pause 60s]");
+        let messages: Vec<_> = parsed.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "pause must be longer than 0s",
+                "pause must be longer than 0s",
+                "pause must be at most 60s",
+            ]
+        );
+        assert_eq!(
+            parsed.document.lines[0].inlines.last(),
+            Some(&Inline::Pause { seconds: 60.0 })
+        );
+    }
+
+    #[test]
+    fn escapes_are_literal() {
+        let parsed = parse(r"\[pause 1s\] and \*\*");
+        assert!(parsed.errors.is_empty());
+        assert_eq!(
+            parsed.document.lines[0].inlines,
+            vec![text("[pause 1s] and **")]
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_position_and_keeps_text() {
+        let parsed = parse("first line\n你好 [speed 9] **open");
+        assert_eq!(
+            parsed.errors,
+            vec![
+                ParseError {
+                    line: 2,
+                    column: 4,
+                    length: 9,
+                    message: "speed must be between 0.25 and 4".to_string(),
+                },
+                ParseError {
+                    line: 2,
+                    column: 14,
+                    length: 2,
+                    message: "unclosed emphasis, expected closing `**`".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            parsed.document.lines[1].inlines,
+            vec![text("你好 [speed 9] **open")]
+        );
+    }
+
+    #[test]
+    fn unknown_and_unclosed_cues() {
+        let parsed = parse("[laughs] and [pause");
+        let messages: Vec<_> = parsed.errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec!["unknown cue `laughs`", "unclosed cue, expected `]`"]
+        );
+        assert_eq!(parsed.errors[1].column, 14);
+    }
+}
@@ -0,0 +1,131 @@
+//! Monitor enumeration and pinning the prompter to a chosen monitor.
+//!
+//! The pinned monitor is stored by key (see `window_state::monitor_key`) in
+//! the settings. While it is disconnected the window falls back to the
+//! primary monitor; a watcher thread moves it back once it reappears.
+
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Monitor, State};
+
+use crate::config::{Config, Settings};
+use crate::placement;
+use crate::window_state::{monitor_key, WindowState};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub key: String,
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+pub fn available(app: &AppHandle) -> Vec<Monitor> {
+    app.available_monitors().unwrap_or_default()
+}
+
+pub fn find(app: &AppHandle, key: &str) -> Option<Monitor> {
+    available(app)
+        .into_iter()
+        .find(|monitor| monitor_key(monitor) == key)
+}
+
+/// Monitor the window belongs on: the pinned one if connected, otherwise the
+/// one it was last on, otherwise the primary monitor.
+fn target_monitor(app: &AppHandle) -> Option<Monitor> {
+    let pinned = app.state::<Config>().get().target_monitor;
+    let preferred = pinned.or_else(|| app.state::<WindowState>().last_monitor());
+    preferred
+        .and_then(|key| find(app, &key))
+        .or_else(|| app.primary_monitor().ok().flatten())
+}
+
+/// Moves the main window onto its target monitor, at the geometry last used
+/// there or else at the configured placement.
+pub fn position_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let Some(monitor) = target_monitor(app) else {
+        return;
+    };
+    if !app
+        .state::<WindowState>()
+        .restore_on(&window.as_ref().window(), &monitor)
+    {
+        let placement = app.state::<Config>().get().placement;
+        let size = (placement::DEFAULT_WIDTH, placement::DEFAULT_HEIGHT);
+        placement::apply(&window, &monitor, placement, Some(size));
+    }
+}
+
+/// Repositions the window whenever the pinned monitor disconnects or
+/// reconnects.
+pub fn spawn_watcher(app: AppHandle) {
+    thread::spawn(move || {
+        let connected = |app: &AppHandle| {
+            app.state::<Config>()
+                .get()
+                .target_monitor
+                .map(|key| find(app, &key).is_some())
+        };
+        let mut was_connected = connected(&app);
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let is_connected = connected(&app);
+            if is_connected.is_some() && is_connected != was_connected {
+                position_main_window(&app);
+            }
+            was_connected = is_connected;
+        }
+    });
+}
+
+#[tauri::command]
+pub fn list_monitors(app: AppHandle) -> Vec<MonitorInfo> {
+    let primary = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| monitor_key(&monitor));
+    available(&app)
+        .iter()
+        .map(|monitor| {
+            let key = monitor_key(monitor);
+            MonitorInfo {
+                primary: primary.as_ref() == Some(&key),
+                key,
+                name: monitor.name().cloned(),
+                x: monitor.position().x,
+                y: monitor.position().y,
+                width: monitor.size().width,
+                height: monitor.size().height,
+                scale_factor: monitor.scale_factor(),
+            }
+        })
+        .collect()
+}
+
+/// Pins the prompter to the monitor with `key`, or unpins it with `None`.
+#[tauri::command]
+pub fn set_target_monitor(
+    app: AppHandle,
+    config: State<'_, Config>,
+    key: Option<String>,
+) -> Result<Settings, String> {
+    if let Some(key) = &key {
+        find(&app, key).ok_or("monitor is not connected")?;
+    }
+    let settings = config.update(serde_json::json!({ "targetMonitor": key }))?;
+    position_main_window(&app);
+    Ok(settings)
+}
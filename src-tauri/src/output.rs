@@ -0,0 +1,94 @@
+//! The talent window: the prompter text alone, fullscreen on the monitor the
+//! talent reads from, with no controls. It renders the same playback state as
+//! the operator console in `main` and is the only window the mirror setting
+//! applies to.
+
+use tauri::{
+    AppHandle, LogicalPosition, Manager, Monitor, State, WebviewUrl, WebviewWindowBuilder,
+};
+
+use crate::capture;
+use crate::config::{Config, Settings};
+use crate::monitors;
+use crate::placement::monitor_bounds;
+use crate::window_state::monitor_key;
+
+pub const OUTPUT_LABEL: &str = "talent";
+
+/// The configured output monitor, else the first one that is not primary,
+/// else the primary one.
+fn output_monitor(app: &AppHandle) -> Option<Monitor> {
+    if let Some(monitor) = app
+        .state::<Config>()
+        .get()
+        .output_monitor
+        .and_then(|key| monitors::find(app, &key))
+    {
+        return Some(monitor);
+    }
+    let primary = app.primary_monitor().ok().flatten();
+    let primary_key = primary.as_ref().map(monitor_key);
+    monitors::available(app)
+        .into_iter()
+        .find(|monitor| Some(monitor_key(monitor)) != primary_key)
+        .or(primary)
+}
+
+/// Opens the talent window fullscreen on its monitor, or moves it there if it
+/// is already open.
+pub fn open(app: &AppHandle) -> Result<(), String> {
+    let window = match app.get_webview_window(OUTPUT_LABEL) {
+        Some(window) => window,
+        None => {
+            let window =
+                WebviewWindowBuilder::new(app, OUTPUT_LABEL, WebviewUrl::App("index.html".into()))
+                    .title("Flash Prompter 输出")
+                    .decorations(false)
+                    .always_on_top(true)
+                    .visible(false)
+                    .build()
+                    .map_err(|e| e.to_string())?;
+            capture::apply(app, &window);
+            window
+        }
+    };
+    if let Some(monitor) = output_monitor(app) {
+        let bounds = monitor_bounds(&monitor);
+        let _ = window.set_fullscreen(false);
+        let _ = window.set_position(LogicalPosition::new(bounds.x, bounds.y));
+    }
+    let _ = window.set_fullscreen(true);
+    window.show().map_err(|e| e.to_string())
+}
+
+pub fn close(app: &AppHandle) -> Result<(), String> {
+    match app.get_webview_window(OUTPUT_LABEL) {
+        Some(window) => window.close().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub fn open_output_window(app: AppHandle) -> Result<(), String> {
+    open(&app)
+}
+
+#[tauri::command]
+pub fn close_output_window(app: AppHandle) -> Result<(), String> {
+    close(&app)
+}
+
+/// Chooses the talent monitor (`None` picks automatically) and moves an open
+/// talent window there.
+#[tauri::command]
+pub fn set_output_monitor(
+    app: AppHandle,
+    config: State<'_, Config>,
+    key: Option<String>,
+) -> Result<Settings, String> {
+    let settings = config.update(serde_json::json!({ "outputMonitor": key }))?;
+    if app.get_webview_window(OUTPUT_LABEL).is_some() {
+        open(&app)?;
+    }
+    Ok(settings)
+}
@@ -0,0 +1,43 @@
+//! Click-through overlay for reading over a video call.
+//!
+//! The main window is created transparent (see `tauri.conf.json`) and the
+//! view paints the prompter background with the configured opacity. Here the
+//! window itself drops its frame and stops taking mouse input, so clicks land
+//! on the call underneath. The window cannot be clicked while the overlay is
+//! on, which is why the toggle hotkey is the way back.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::config::Overlay;
+
+pub fn apply(window: &WebviewWindow, overlay: Overlay) {
+    let _ = window.set_ignore_cursor_events(overlay.enabled);
+    let _ = window.set_decorations(!overlay.enabled);
+    let _ = window.set_shadow(!overlay.enabled);
+    let _ = window.set_always_on_top(true);
+}
+
+/// Re-applies the overlay to the main window when it is switched on or off.
+/// Opacity is handled by the view alone.
+pub struct OverlaySync {
+    enabled: AtomicBool,
+}
+
+impl OverlaySync {
+    pub fn new(overlay: Overlay) -> Self {
+        Self {
+            enabled: AtomicBool::new(overlay.enabled),
+        }
+    }
+
+    pub fn update(&self, app: &AppHandle, overlay: Overlay) {
+        if self.enabled.swap(overlay.enabled, Ordering::SeqCst) == overlay.enabled {
+            return;
+        }
+        if let Some(window) = app.get_webview_window("main") {
+            apply(&window, overlay);
+        }
+    }
+}
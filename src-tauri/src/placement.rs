@@ -0,0 +1,251 @@
+//! Where the prompter window goes on a monitor. Everything here works in
+//! logical pixels so the same placement looks the same at any scale factor.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager, Monitor, State, WebviewWindow};
+
+use crate::config::{Config, Settings, WindowGeometry};
+
+pub const DEFAULT_WIDTH: f64 = 420.0;
+pub const DEFAULT_HEIGHT: f64 = 300.0;
+/// The original placement sat this far above the monitor's center.
+const CENTERED_RAISE: f64 = 300.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Anchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum Placement {
+    /// Centered horizontally, raised towards the top of the screen.
+    #[default]
+    Centered,
+    /// Top-center, flush with the top edge, right under a monitor-mounted camera.
+    UnderWebcam,
+    /// Flush with the left edge, at the top.
+    LeftGutter,
+    /// Flush with the right edge, at the top.
+    RightGutter,
+    /// `anchor` point of the window sits on the same point of the monitor,
+    /// shifted by the offset.
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        anchor: Anchor,
+        offset_x: f64,
+        offset_y: f64,
+    },
+}
+
+/// Logical bounds of a monitor from its physical position and size.
+pub fn logical_bounds(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale_factor: f64,
+) -> WindowGeometry {
+    WindowGeometry {
+        x: x as f64 / scale_factor,
+        y: y as f64 / scale_factor,
+        width: width as f64 / scale_factor,
+        height: height as f64 / scale_factor,
+    }
+}
+
+pub fn monitor_bounds(monitor: &Monitor) -> WindowGeometry {
+    let position = monitor.position();
+    let size = monitor.size();
+    logical_bounds(
+        position.x,
+        position.y,
+        size.width,
+        size.height,
+        monitor.scale_factor(),
+    )
+}
+
+/// Computes the window geometry for `placement` on a monitor with logical
+/// `bounds`. The window is shrunk to fit and kept fully on the monitor.
+pub fn place(
+    placement: Placement,
+    bounds: WindowGeometry,
+    width: f64,
+    height: f64,
+) -> WindowGeometry {
+    let width = width.min(bounds.width);
+    let height = height.min(bounds.height);
+    let free_x = bounds.width - width;
+    let free_y = bounds.height - height;
+    let (x, y) = match placement {
+        Placement::Centered => (free_x / 2.0, free_y / 2.0 - CENTERED_RAISE),
+        Placement::UnderWebcam => (free_x / 2.0, 0.0),
+        Placement::LeftGutter => (0.0, 0.0),
+        Placement::RightGutter => (free_x, 0.0),
+        Placement::Custom {
+            anchor,
+            offset_x,
+            offset_y,
+        } => {
+            let (fx, fy) = match anchor {
+                Anchor::TopLeft => (0.0, 0.0),
+                Anchor::TopCenter => (0.5, 0.0),
+                Anchor::TopRight => (1.0, 0.0),
+                Anchor::CenterLeft => (0.0, 0.5),
+                Anchor::Center => (0.5, 0.5),
+                Anchor::CenterRight => (1.0, 0.5),
+                Anchor::BottomLeft => (0.0, 1.0),
+                Anchor::BottomCenter => (0.5, 1.0),
+                Anchor::BottomRight => (1.0, 1.0),
+            };
+            (free_x * fx + offset_x, free_y * fy + offset_y)
+        }
+    };
+    WindowGeometry {
+        x: bounds.x + x.clamp(0.0, free_x),
+        y: bounds.y + y.clamp(0.0, free_y),
+        width,
+        height,
+    }
+}
+
+/// Applies `placement` to `window` on `monitor`, keeping the window's current
+/// size unless `size` is given.
+pub fn apply(
+    window: &WebviewWindow,
+    monitor: &Monitor,
+    placement: Placement,
+    size: Option<(f64, f64)>,
+) {
+    let (width, height) = size.unwrap_or_else(|| {
+        window
+            .outer_size()
+            .map(|size| {
+                let size = size.to_logical::<f64>(monitor.scale_factor());
+                (size.width, size.height)
+            })
+            .unwrap_or((DEFAULT_WIDTH, DEFAULT_HEIGHT))
+    });
+    let geometry = place(placement, monitor_bounds(monitor), width, height);
+    let _ = window.set_size(LogicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(LogicalPosition::new(geometry.x, geometry.y));
+}
+
+/// Saves the placement setting and moves the main window accordingly.
+#[tauri::command]
+pub fn apply_placement(
+    app: AppHandle,
+    config: State<'_, Config>,
+    placement: Placement,
+) -> Result<Settings, String> {
+    let value = serde_json::to_value(placement).map_err(|e| e.to_string())?;
+    let settings = config.update(serde_json::json!({ "placement": value }))?;
+    let window = app
+        .get_webview_window("main")
+        .ok_or("main window is missing")?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .ok_or("window is not on any monitor")?;
+    apply(&window, &monitor, placement, None);
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn logical_bounds_divide_by_scale_factor() {
+        assert_eq!(
+            logical_bounds(-3840, -432, 3840, 2160, 2.0),
+            rect(-1920.0, -216.0, 1920.0, 1080.0)
+        );
+        assert_eq!(
+            logical_bounds(2880, 0, 2880, 1620, 1.5),
+            rect(1920.0, 0.0, 1920.0, 1080.0)
+        );
+    }
+
+    #[test]
+    fn built_in_modes_on_a_scaled_secondary_monitor() {
+        let bounds = logical_bounds(-2560, -300, 2560, 1440, 1.25);
+        assert_eq!(bounds, rect(-2048.0, -240.0, 2048.0, 1152.0));
+        assert_eq!(
+            place(Placement::UnderWebcam, bounds, 420.0, 300.0),
+            rect(-1234.0, -240.0, 420.0, 300.0)
+        );
+        assert_eq!(
+            place(Placement::LeftGutter, bounds, 420.0, 300.0),
+            rect(-2048.0, -240.0, 420.0, 300.0)
+        );
+        assert_eq!(
+            place(Placement::RightGutter, bounds, 420.0, 300.0),
+            rect(-420.0, -240.0, 420.0, 300.0)
+        );
+        assert_eq!(
+            place(Placement::Centered, bounds, 420.0, 300.0),
+            rect(-1234.0, -114.0, 420.0, 300.0)
+        );
+    }
+
+    #[test]
+    fn centered_matches_the_original_placement() {
+        let bounds = logical_bounds(0, 0, 1920, 1080, 1.0);
+        assert_eq!(
+            place(Placement::Centered, bounds, 420.0, 300.0),
+            rect(750.0, 90.0, 420.0, 300.0)
+        );
+    }
+
+    #[test]
+    fn custom_anchor_offsets_are_clamped_to_the_monitor() {
+        let bounds = logical_bounds(-1920, 0, 1920, 1080, 1.0);
+        let bottom_right = Placement::Custom {
+            anchor: Anchor::BottomRight,
+            offset_x: -20.0,
+            offset_y: 50.0,
+        };
+        assert_eq!(
+            place(bottom_right, bounds, 400.0, 200.0),
+            rect(-420.0, 880.0, 400.0, 200.0)
+        );
+        let oversized = place(Placement::UnderWebcam, bounds, 2400.0, 300.0);
+        assert_eq!(oversized, rect(-1920.0, 0.0, 1920.0, 300.0));
+    }
+
+    #[test]
+    fn placement_serializes_with_a_mode_tag() {
+        let custom: Placement = serde_json::from_str(
+            r#"{"mode":"custom","anchor":"topCenter","offsetX":0,"offsetY":24}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            custom,
+            Placement::Custom {
+                anchor: Anchor::TopCenter,
+                offset_x: 0.0,
+                offset_y: 24.0,
+            }
+        );
+    }
+}
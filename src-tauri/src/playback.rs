@@ -0,0 +1,646 @@
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::markup::{self, Document};
+use crate::timing::{self, TimingEstimate};
+
+pub const POSITION_EVENT: &str = "playback://position";
+pub const DOCUMENT_EVENT: &str = "playback://document";
+pub const TICK: Duration = Duration::from_millis(33);
+const DEFAULT_RATE: f64 = 60.0;
+/// How far into a line "line back" still counts as being at its start.
+const LINE_BACK_GRACE: f64 = 0.75;
+
+/// Source of monotonic time for the timeline, injectable so tests can step it.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlaybackState {
+    Idle,
+    Playing,
+    Paused,
+    Finished,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackSnapshot {
+    pub state: PlaybackState,
+    pub progress: f64,
+    pub elapsed_seconds: f64,
+    pub duration_seconds: f64,
+    pub rate: f64,
+    pub line: usize,
+    pub line_text: String,
+    /// How far the current line has been read, from 0 to 1.
+    pub line_progress: f64,
+    /// Whether the script plays on `[at ...]` cue times instead of the rate.
+    pub timed: bool,
+}
+
+/// Deterministic scroll timeline. The position is derived from an anchor
+/// (elapsed seconds at a clock reading) rather than accumulated per frame.
+pub struct Timeline {
+    /// The loaded script markup.
+    text: String,
+    document: Document,
+    lines: Vec<String>,
+    estimate: TimingEstimate,
+    state: PlaybackState,
+    anchor_elapsed: f64,
+    anchor_time: Duration,
+}
+
+impl Timeline {
+    pub fn new(rate: f64) -> Self {
+        Self {
+            text: String::new(),
+            document: Document::default(),
+            lines: Vec::new(),
+            estimate: timing::estimate("", rate),
+            state: PlaybackState::Idle,
+            anchor_elapsed: 0.0,
+            anchor_time: Duration::ZERO,
+        }
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.estimate.total_seconds
+    }
+
+    pub fn load(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.document = markup::parse(text).document;
+        self.lines = self
+            .document
+            .lines
+            .iter()
+            .map(|line| line.plain_text())
+            .collect();
+        self.estimate = timing::estimate_document(&self.document, self.estimate.rate);
+        self.stop();
+    }
+
+    pub fn play(&mut self, now: Duration) {
+        if self.state == PlaybackState::Playing {
+            return;
+        }
+        if self.state == PlaybackState::Finished {
+            self.anchor_elapsed = 0.0;
+        }
+        self.state = PlaybackState::Playing;
+        self.anchor_time = now;
+    }
+
+    pub fn pause(&mut self, now: Duration) {
+        if self.state == PlaybackState::Playing {
+            self.anchor_elapsed = self.elapsed(now);
+            self.state = PlaybackState::Paused;
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.state = PlaybackState::Idle;
+        self.anchor_elapsed = 0.0;
+    }
+
+    pub fn seek(&mut self, progress: f64, now: Duration) {
+        self.anchor_elapsed = progress.clamp(0.0, 1.0) * self.duration();
+        self.anchor_time = now;
+        if self.state == PlaybackState::Finished {
+            self.state = PlaybackState::Paused;
+        }
+    }
+
+    /// Moves the position by `seconds`, backwards when negative.
+    pub fn nudge(&mut self, seconds: f64, now: Duration) {
+        self.anchor_elapsed = (self.elapsed(now) + seconds).clamp(0.0, self.duration());
+        self.anchor_time = now;
+        if self.state == PlaybackState::Finished && seconds < 0.0 {
+            self.state = PlaybackState::Paused;
+        }
+    }
+
+    /// Jumps to the start of the current line, or of the previous spoken line
+    /// when the current one has only just started.
+    pub fn line_back(&mut self, now: Duration) {
+        let elapsed = self.elapsed(now);
+        let lines = &self.estimate.lines;
+        let current = lines
+            .iter()
+            .rposition(|line| line.start_seconds <= elapsed)
+            .unwrap_or(0);
+        let target = match lines.get(current) {
+            Some(line) if elapsed - line.start_seconds >= LINE_BACK_GRACE => line.start_seconds,
+            _ => lines[..current]
+                .iter()
+                .rev()
+                .find(|line| line.units > 0.0)
+                .map(|line| line.start_seconds)
+                .unwrap_or(0.0),
+        };
+        self.anchor_elapsed = target;
+        self.anchor_time = now;
+        if self.state == PlaybackState::Finished {
+            self.state = PlaybackState::Paused;
+        }
+    }
+
+    /// Re-estimates the script at the new rate, keeping the relative position.
+    pub fn set_rate(&mut self, rate: f64, now: Duration) {
+        let progress = self.progress(now);
+        self.estimate = timing::estimate_document(&self.document, rate);
+        self.anchor_elapsed = progress * self.duration();
+        self.anchor_time = now;
+    }
+
+    pub fn elapsed(&self, now: Duration) -> f64 {
+        match self.state {
+            PlaybackState::Playing => {
+                let running = now.saturating_sub(self.anchor_time).as_secs_f64();
+                (self.anchor_elapsed + running).min(self.duration())
+            }
+            _ => self.anchor_elapsed,
+        }
+    }
+
+    pub fn progress(&self, now: Duration) -> f64 {
+        if self.duration() <= 0.0 {
+            return 0.0;
+        }
+        self.elapsed(now) / self.duration()
+    }
+
+    /// Moves a running timeline to `Finished` once it reaches the end.
+    pub fn settle(&mut self, now: Duration) {
+        if self.state == PlaybackState::Playing && self.elapsed(now) >= self.duration() {
+            self.anchor_elapsed = self.duration();
+            self.state = PlaybackState::Finished;
+        }
+    }
+
+    pub fn snapshot(&self, now: Duration) -> PlaybackSnapshot {
+        let elapsed = self.elapsed(now);
+        let line = self
+            .estimate
+            .lines
+            .iter()
+            .rposition(|line| line.start_seconds <= elapsed)
+            .unwrap_or(0);
+        let line_progress = self.estimate.lines.get(line).map_or(0.0, |timing| {
+            let reading = (timing.seconds - timing.pause_seconds).max(f64::EPSILON);
+            ((elapsed - timing.start_seconds) / reading).clamp(0.0, 1.0)
+        });
+        PlaybackSnapshot {
+            state: self.state,
+            progress: self.progress(now),
+            elapsed_seconds: elapsed,
+            duration_seconds: self.duration(),
+            rate: self.estimate.rate,
+            line,
+            line_text: self.lines.get(line).cloned().unwrap_or_default(),
+            line_progress,
+            timed: self.estimate.timed,
+        }
+    }
+}
+
+type Listener = Box<dyn Fn(&PlaybackSnapshot) + Send + Sync>;
+
+struct Inner {
+    clock: Arc<dyn Clock>,
+    timeline: Mutex<Timeline>,
+    listeners: Mutex<Vec<Listener>>,
+}
+
+/// Shared playback engine. Every state change and every tick while playing is
+/// pushed to the subscribed listeners.
+#[derive(Clone)]
+pub struct Playback {
+    inner: Arc<Inner>,
+}
+
+impl Playback {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                clock,
+                timeline: Mutex::new(Timeline::new(DEFAULT_RATE)),
+                listeners: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    pub fn subscribe(&self, listener: impl Fn(&PlaybackSnapshot) + Send + Sync + 'static) {
+        if let Ok(mut listeners) = self.inner.listeners.lock() {
+            listeners.push(Box::new(listener));
+        }
+    }
+
+    /// The current position. Reaching the end here finishes the timeline
+    /// like a tick would, so listeners still hear about it.
+    pub fn snapshot(&self) -> PlaybackSnapshot {
+        let now = self.inner.clock.now();
+        let (snapshot, finished) = {
+            let mut timeline = self.timeline();
+            let before = timeline.state;
+            timeline.settle(now);
+            (timeline.snapshot(now), timeline.state != before)
+        };
+        if finished {
+            self.notify(&snapshot);
+        }
+        snapshot
+    }
+
+    pub fn document(&self) -> Document {
+        self.timeline().document.clone()
+    }
+
+    pub fn load(&self, text: &str) -> PlaybackSnapshot {
+        self.update(|timeline, _| timeline.load(text))
+    }
+
+    /// Loads `text` unless it is already loaded. The editor calls this when
+    /// its text changes, which includes showing a script that was loaded
+    /// here first; reloading it would stop playback started since.
+    pub fn sync(&self, text: &str) -> PlaybackSnapshot {
+        self.update(|timeline, _| {
+            if timeline.text != text {
+                timeline.load(text);
+            }
+        })
+    }
+
+    pub fn play(&self) -> PlaybackSnapshot {
+        self.update(|timeline, now| timeline.play(now))
+    }
+
+    pub fn pause(&self) -> PlaybackSnapshot {
+        self.update(|timeline, now| timeline.pause(now))
+    }
+
+    pub fn toggle(&self) -> PlaybackSnapshot {
+        self.update(|timeline, now| {
+            if timeline.state == PlaybackState::Playing {
+                timeline.pause(now);
+            } else {
+                timeline.play(now);
+            }
+        })
+    }
+
+    pub fn stop(&self) -> PlaybackSnapshot {
+        self.update(|timeline, _| timeline.stop())
+    }
+
+    pub fn seek(&self, progress: f64) -> PlaybackSnapshot {
+        self.update(|timeline, now| timeline.seek(progress, now))
+    }
+
+    pub fn nudge(&self, seconds: f64) -> PlaybackSnapshot {
+        self.update(|timeline, now| timeline.nudge(seconds, now))
+    }
+
+    pub fn line_back(&self) -> PlaybackSnapshot {
+        self.update(|timeline, now| timeline.line_back(now))
+    }
+
+    pub fn set_rate(&self, rate: f64) -> PlaybackSnapshot {
+        self.update(|timeline, now| timeline.set_rate(rate, now))
+    }
+
+    /// Advances a running timeline and notifies listeners. Returns `None`
+    /// when nothing is playing so idle ticks stay silent.
+    pub fn tick(&self) -> Option<PlaybackSnapshot> {
+        let now = self.inner.clock.now();
+        let snapshot = {
+            let mut timeline = self.timeline();
+            if timeline.state != PlaybackState::Playing {
+                return None;
+            }
+            timeline.settle(now);
+            timeline.snapshot(now)
+        };
+        self.notify(&snapshot);
+        Some(snapshot)
+    }
+
+    /// Ticks on a background thread until the engine is dropped.
+    pub fn spawn_ticker(&self, interval: Duration) {
+        let weak: Weak<Inner> = Arc::downgrade(&self.inner);
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            match weak.upgrade() {
+                Some(inner) => {
+                    Playback { inner }.tick();
+                }
+                None => break,
+            }
+        });
+    }
+
+    fn timeline(&self) -> std::sync::MutexGuard<'_, Timeline> {
+        self.inner
+            .timeline
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn update(&self, change: impl FnOnce(&mut Timeline, Duration)) -> PlaybackSnapshot {
+        let now = self.inner.clock.now();
+        let snapshot = {
+            let mut timeline = self.timeline();
+            change(&mut timeline, now);
+            timeline.settle(now);
+            timeline.snapshot(now)
+        };
+        self.notify(&snapshot);
+        snapshot
+    }
+
+    fn notify(&self, snapshot: &PlaybackSnapshot) {
+        if let Ok(listeners) = self.inner.listeners.lock() {
+            for listener in listeners.iter() {
+                listener(snapshot);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn playback_load(
+    app: AppHandle,
+    playback: State<'_, Playback>,
+    text: String,
+) -> PlaybackSnapshot {
+    let snapshot = playback.sync(&text);
+    let _ = app.emit(DOCUMENT_EVENT, playback.document());
+    snapshot
+}
+
+#[tauri::command]
+pub fn playback_document(playback: State<'_, Playback>) -> Document {
+    playback.document()
+}
+
+#[tauri::command]
+pub fn playback_play(playback: State<'_, Playback>) -> PlaybackSnapshot {
+    playback.play()
+}
+
+#[tauri::command]
+pub fn playback_pause(playback: State<'_, Playback>) -> PlaybackSnapshot {
+    playback.pause()
+}
+
+#[tauri::command]
+pub fn playback_toggle(playback: State<'_, Playback>) -> PlaybackSnapshot {
+    playback.toggle()
+}
+
+#[tauri::command]
+pub fn playback_stop(playback: State<'_, Playback>) -> PlaybackSnapshot {
+    playback.stop()
+}
+
+#[tauri::command]
+pub fn playback_seek(playback: State<'_, Playback>, progress: f64) -> PlaybackSnapshot {
+    playback.seek(progress)
+}
+
+#[tauri::command]
+pub fn playback_set_rate(playback: State<'_, Playback>, rate: f64) -> PlaybackSnapshot {
+    playback.set_rate(rate)
+}
+
+#[tauri::command]
+pub fn playback_status(playback: State<'_, Playback>) -> PlaybackSnapshot {
+    playback.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct ManualClock(Mutex<Duration>);
+
+    impl ManualClock {
+        fn advance(&self, seconds: f64) {
+            *self.0.lock().unwrap() += Duration::from_secs_f64(seconds);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Duration {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    /// Ten words at 60 units per minute: ten seconds without pauses.
+    const SCRIPT: &str = "one two three four five six seven eight nine ten";
+
+    fn engine() -> (Arc<ManualClock>, Playback) {
+        let clock = Arc::new(ManualClock::default());
+        let playback = Playback::new(clock.clone());
+        playback.set_rate(60.0);
+        playback.load(SCRIPT);
+        (clock, playback)
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn sync_keeps_playing_the_loaded_script() {
+        let (clock, playback) = engine();
+        playback.play();
+        clock.advance(2.0);
+        let snapshot = playback.sync(SCRIPT);
+        assert_eq!(snapshot.state, PlaybackState::Playing);
+        assert_close(snapshot.elapsed_seconds, 2.0);
+        assert_eq!(playback.sync("one two").state, PlaybackState::Idle);
+    }
+
+    #[test]
+    fn play_advances_with_the_clock() {
+        let (clock, playback) = engine();
+        assert_close(playback.snapshot().duration_seconds, 10.0);
+        playback.play();
+        clock.advance(2.5);
+        let snapshot = playback.snapshot();
+        assert_eq!(snapshot.state, PlaybackState::Playing);
+        assert_close(snapshot.progress, 0.25);
+    }
+
+    #[test]
+    fn pause_freezes_position() {
+        let (clock, playback) = engine();
+        playback.play();
+        clock.advance(3.0);
+        playback.pause();
+        clock.advance(5.0);
+        assert_close(playback.snapshot().elapsed_seconds, 3.0);
+        playback.play();
+        clock.advance(1.0);
+        assert_close(playback.snapshot().elapsed_seconds, 4.0);
+    }
+
+    #[test]
+    fn seek_moves_a_running_timeline() {
+        let (clock, playback) = engine();
+        playback.play();
+        clock.advance(1.0);
+        playback.seek(0.5);
+        clock.advance(1.0);
+        assert_close(playback.snapshot().elapsed_seconds, 6.0);
+    }
+
+    #[test]
+    fn rate_change_keeps_progress() {
+        let (clock, playback) = engine();
+        playback.play();
+        clock.advance(5.0);
+        let snapshot = playback.set_rate(120.0);
+        assert_close(snapshot.progress, 0.5);
+        assert_close(snapshot.duration_seconds, 5.0);
+        clock.advance(1.25);
+        assert_close(playback.snapshot().progress, 0.75);
+    }
+
+    #[test]
+    fn finishes_once_and_restarts_on_play() {
+        let (clock, playback) = engine();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        playback.subscribe(move |snapshot| sink.lock().unwrap().push(snapshot.state));
+
+        playback.play();
+        clock.advance(11.0);
+        let finished = playback.tick().unwrap();
+        assert_eq!(finished.state, PlaybackState::Finished);
+        assert_close(finished.progress, 1.0);
+        assert!(playback.tick().is_none());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![PlaybackState::Playing, PlaybackState::Finished]
+        );
+
+        playback.play();
+        assert_close(playback.snapshot().progress, 0.0);
+    }
+
+    #[test]
+    fn reading_the_status_at_the_end_still_reports_finished() {
+        let (clock, playback) = engine();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        playback.subscribe(move |snapshot| sink.lock().unwrap().push(snapshot.state));
+
+        playback.play();
+        clock.advance(11.0);
+        assert_eq!(playback.snapshot().state, PlaybackState::Finished);
+        assert_eq!(playback.snapshot().state, PlaybackState::Finished);
+        assert!(playback.tick().is_none());
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![PlaybackState::Playing, PlaybackState::Finished]
+        );
+    }
+
+    #[test]
+    fn stop_rewinds() {
+        let (clock, playback) = engine();
+        playback.play();
+        clock.advance(4.0);
+        let snapshot = playback.stop();
+        assert_eq!(snapshot.state, PlaybackState::Idle);
+        assert_close(snapshot.elapsed_seconds, 0.0);
+    }
+
+    #[test]
+    fn reports_current_line() {
+        let (clock, playback) = engine();
+        playback.load("one two\n**three** four[note: smile]");
+        playback.play();
+        clock.advance(2.5);
+        let snapshot = playback.snapshot();
+        assert_eq!(snapshot.line, 1);
+        assert_eq!(snapshot.line_text, "three four");
+    }
+
+    #[test]
+    fn line_back_returns_to_line_starts() {
+        let (clock, playback) = engine();
+        playback.load("one two\n\nthree four five");
+        playback.play();
+        clock.advance(4.0);
+        assert_close(playback.line_back().elapsed_seconds, 2.6);
+        assert_close(playback.line_back().elapsed_seconds, 0.0);
+    }
+
+    #[test]
+    fn timed_scripts_follow_cue_times() {
+        let (clock, playback) = engine();
+        playback.load("[section Reel 1]\n[at 0:02-0:04]one two\nthree four\n[at 0:06]five");
+        let snapshot = playback.set_rate(600.0);
+        assert!(snapshot.timed);
+        assert_close(snapshot.duration_seconds, 6.1);
+
+        playback.play();
+        clock.advance(1.0);
+        assert_eq!(playback.snapshot().line, 0);
+        clock.advance(1.5);
+        let snapshot = playback.snapshot();
+        assert_eq!(snapshot.line, 1);
+        assert_close(snapshot.line_progress, 0.5);
+        clock.advance(2.0);
+        let snapshot = playback.snapshot();
+        assert_eq!((snapshot.line, snapshot.line_progress), (2, 1.0));
+        clock.advance(1.5);
+        assert_eq!(playback.snapshot().line_text, "five");
+    }
+
+    #[test]
+    fn nudge_is_clamped_to_the_script() {
+        let (clock, playback) = engine();
+        playback.play();
+        clock.advance(1.0);
+        assert_close(playback.nudge(2.0).elapsed_seconds, 3.0);
+        assert_close(playback.nudge(-5.0).elapsed_seconds, 0.0);
+        assert_close(playback.nudge(20.0).elapsed_seconds, 10.0);
+    }
+}
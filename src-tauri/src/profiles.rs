@@ -0,0 +1,99 @@
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::config::{Config, Settings, WindowGeometry};
+
+const BUNDLE_EXTENSION: &str = "json";
+
+fn main_window_geometry(app: &AppHandle) -> Option<WindowGeometry> {
+    let window = app.get_webview_window("main")?;
+    let scale_factor = window.scale_factor().ok()?;
+    let position = window
+        .outer_position()
+        .ok()?
+        .to_logical::<f64>(scale_factor);
+    let size = window.outer_size().ok()?.to_logical::<f64>(scale_factor);
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+fn place_main_window(app: &AppHandle, geometry: WindowGeometry) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_size(LogicalSize::new(geometry.width, geometry.height));
+        let _ = window.set_position(LogicalPosition::new(geometry.x, geometry.y));
+    }
+}
+
+/// Activates a profile and moves the window to its saved geometry.
+pub fn activate(app: &AppHandle, settings: Result<Settings, String>) -> Result<Settings, String> {
+    let settings = settings?;
+    let window = settings
+        .active_profile
+        .as_deref()
+        .and_then(|name| settings.profile(name))
+        .and_then(|profile| profile.window);
+    if let Some(geometry) = window {
+        place_main_window(app, geometry);
+    }
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn switch_profile(
+    app: AppHandle,
+    config: State<'_, Config>,
+    name: String,
+) -> Result<Settings, String> {
+    activate(&app, config.switch_profile(&name))
+}
+
+#[tauri::command]
+pub fn save_profile(
+    app: AppHandle,
+    config: State<'_, Config>,
+    name: String,
+) -> Result<Settings, String> {
+    config.save_profile(&name, main_window_geometry(&app))
+}
+
+#[tauri::command]
+pub fn delete_profile(config: State<'_, Config>, name: String) -> Result<Settings, String> {
+    config.delete_profile(&name)
+}
+
+/// Asks where to save and writes every profile there. Returns false when the
+/// dialog was cancelled.
+#[tauri::command]
+pub async fn export_profiles(app: AppHandle) -> Result<bool, String> {
+    let Some(path) = app
+        .dialog()
+        .file()
+        .add_filter("Flash Prompter profiles", &[BUNDLE_EXTENSION])
+        .set_file_name("flash-prompter-profiles.json")
+        .blocking_save_file()
+    else {
+        return Ok(false);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    app.state::<Config>().export_profiles(&path)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn import_profiles(app: AppHandle) -> Result<Settings, String> {
+    let config = app.state::<Config>();
+    let Some(path) = app
+        .dialog()
+        .file()
+        .add_filter("Flash Prompter profiles", &[BUNDLE_EXTENSION])
+        .blocking_pick_file()
+    else {
+        return Ok(config.get());
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    config.import_profiles(&path)
+}
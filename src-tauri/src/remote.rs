@@ -0,0 +1,820 @@
+//! Optional HTTP/WebSocket server for controlling playback from another machine.
+//!
+//! `GET /` serves the phone remote page. It exchanges the one-time pairing
+//! code from the QR link for the token via `POST /api/pair` with
+//! `{"code": "123456"}`. Every other request must carry the token, either as
+//! `Authorization: Bearer <token>` or as a `?token=` query parameter.
+//!
+//! - `GET /api/status` returns the current playback snapshot.
+//! - `POST /api/play`, `/api/pause`, `/api/toggle`, `/api/stop`.
+//! - `POST /api/seek` with `{"progress": 0.5}`.
+//! - `POST /api/nudge` with `{"seconds": -2}`.
+//! - `POST /api/speed` with `{"rate": 120}`.
+//! - `POST /api/mirror` flips the output window.
+//! - `GET /api/ws` streams snapshots as JSON text frames and accepts
+//!   [`Control`] messages such as `{"action": "play"}`.
+//!
+//! Controls can save settings, so they run on a blocking thread rather than
+//! on the async runtime. Sockets are pushed the snapshots playback publishes
+//! instead of polling for them.
+
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::oneshot;
+
+use crate::control::{Control, Controller};
+use crate::playback::PlaybackSnapshot;
+
+const DEFAULT_PORT: u16 = 7345;
+const BIND_RETRIES: u32 = 10;
+const BIND_RETRY_DELAY: Duration = Duration::from_millis(50);
+const PAIRING_ATTEMPTS: u32 = 5;
+/// Snapshots a slow socket may fall behind by before it skips to the latest.
+const UPDATE_BUFFER: usize = 16;
+const REMOTE_PAGE: &str = include_str!("../remote/index.html");
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteBind {
+    #[default]
+    Localhost,
+    Lan,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteConfig {
+    pub enabled: bool,
+    pub bind: RemoteBind,
+    pub port: u16,
+    pub token: String,
+}
+
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: RemoteBind::Localhost,
+            port: DEFAULT_PORT,
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteStatus {
+    pub config: RemoteConfig,
+    pub address: Option<String>,
+}
+
+pub struct RunningServer {
+    pub address: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl RunningServer {
+    pub fn stop(self) {
+        let _ = self.shutdown.send(());
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePairing {
+    pub url: String,
+    pub code: String,
+    pub qr_svg: String,
+}
+
+struct PairingCode {
+    code: String,
+    attempts_left: u32,
+}
+
+/// One-time code the phone page trades for the token. A new code replaces
+/// the previous one, and a few wrong guesses invalidate it.
+#[derive(Default)]
+pub struct Pairing {
+    current: Mutex<Option<PairingCode>>,
+}
+
+impl Pairing {
+    pub fn issue(&self) -> Result<String, String> {
+        let mut bytes = [0u8; 4];
+        let random = getrandom::fill(&mut bytes)
+            .map(|()| bytes)
+            .map_err(|e| format!("cannot generate a pairing code: {e}"));
+        self.issue_from(random)
+    }
+
+    /// Replaces the current code with one made from `random`. Without random
+    /// bytes no code is valid, rather than a predictable one.
+    fn issue_from(&self, random: Result<[u8; 4], String>) -> Result<String, String> {
+        let mut current = self.current.lock().map_err(|e| e.to_string())?;
+        *current = None;
+        let code = format!("{:06}", u32::from_le_bytes(random?) % 1_000_000);
+        *current = Some(PairingCode {
+            code: code.clone(),
+            attempts_left: PAIRING_ATTEMPTS,
+        });
+        Ok(code)
+    }
+
+    fn redeem(&self, code: &str) -> bool {
+        let Ok(mut current) = self.current.lock() else {
+            return false;
+        };
+        let Some(pairing) = current.as_mut() else {
+            return false;
+        };
+        if constant_time_eq(pairing.code.as_bytes(), code.as_bytes()) {
+            *current = None;
+            return true;
+        }
+        pairing.attempts_left -= 1;
+        if pairing.attempts_left == 0 {
+            *current = None;
+        }
+        false
+    }
+}
+
+#[derive(Clone)]
+struct ServerState {
+    token: Arc<str>,
+    controller: Arc<dyn Controller>,
+    pairing: Arc<Pairing>,
+    updates: broadcast::Sender<PlaybackSnapshot>,
+}
+
+/// Broadcasts every snapshot `controller` publishes. Subscribe once and keep
+/// the sender across server restarts, since listeners cannot be removed.
+pub fn snapshot_updates(controller: &dyn Controller) -> broadcast::Sender<PlaybackSnapshot> {
+    let (updates, _) = broadcast::channel(UPDATE_BUFFER);
+    let sender = updates.clone();
+    controller.subscribe(Box::new(move |snapshot| {
+        let _ = sender.send(snapshot.clone());
+    }));
+    updates
+}
+
+/// Binds synchronously so address errors surface to the caller, then serves
+/// on the async runtime until [`RunningServer::stop`] is called.
+pub fn start(
+    config: &RemoteConfig,
+    controller: Arc<dyn Controller>,
+    pairing: Arc<Pairing>,
+    updates: broadcast::Sender<PlaybackSnapshot>,
+) -> Result<RunningServer, String> {
+    let ip = match config.bind {
+        RemoteBind::Localhost => Ipv4Addr::LOCALHOST,
+        RemoteBind::Lan => Ipv4Addr::UNSPECIFIED,
+    };
+    let listener = bind((ip, config.port).into())?;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+    let state = ServerState {
+        token: Arc::from(config.token.as_str()),
+        controller,
+        pairing,
+        updates,
+    };
+    let app = router(state);
+    let (shutdown, stopped) = oneshot::channel::<()>();
+    tauri::async_runtime::spawn(async move {
+        let Ok(listener) = tokio::net::TcpListener::from_std(listener) else {
+            return;
+        };
+        let _ = axum::serve(listener, app)
+            .with_graceful_shutdown(async {
+                let _ = stopped.await;
+            })
+            .await;
+    });
+    Ok(RunningServer { address, shutdown })
+}
+
+/// A restarted server may still be releasing the port, so retry briefly.
+fn bind(address: SocketAddr) -> Result<std::net::TcpListener, String> {
+    let mut attempts = 0;
+    loop {
+        match std::net::TcpListener::bind(address) {
+            Ok(listener) => return Ok(listener),
+            Err(err) if err.kind() == std::io::ErrorKind::AddrInUse && attempts < BIND_RETRIES => {
+                attempts += 1;
+                std::thread::sleep(BIND_RETRY_DELAY);
+            }
+            Err(err) => return Err(err.to_string()),
+        }
+    }
+}
+
+fn router(state: ServerState) -> Router {
+    let api = Router::new()
+        .route("/api/status", get(status))
+        .route(
+            "/api/play",
+            post(|s: State<ServerState>| apply(s, Control::Play)),
+        )
+        .route(
+            "/api/pause",
+            post(|s: State<ServerState>| apply(s, Control::Pause)),
+        )
+        .route(
+            "/api/toggle",
+            post(|s: State<ServerState>| apply(s, Control::Toggle)),
+        )
+        .route(
+            "/api/stop",
+            post(|s: State<ServerState>| apply(s, Control::Stop)),
+        )
+        .route(
+            "/api/mirror",
+            post(|s: State<ServerState>| apply(s, Control::ToggleMirror)),
+        )
+        .route("/api/seek", post(seek))
+        .route("/api/nudge", post(nudge))
+        .route("/api/speed", post(speed))
+        .route("/api/ws", get(socket))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize));
+    Router::new()
+        .route("/", get(page))
+        .route("/api/pair", post(pair))
+        .merge(api)
+        .with_state(state)
+}
+
+async fn page() -> Html<&'static str> {
+    Html(REMOTE_PAGE)
+}
+
+#[derive(Deserialize)]
+struct PairBody {
+    code: String,
+}
+
+#[derive(Serialize)]
+struct PairResponse {
+    token: String,
+}
+
+async fn pair(State(state): State<ServerState>, Json(body): Json<PairBody>) -> Response {
+    if !state.pairing.redeem(body.code.trim()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    Json(PairResponse {
+        token: state.token.to_string(),
+    })
+    .into_response()
+}
+
+async fn authorize(State(state): State<ServerState>, request: Request, next: Next) -> Response {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query = request.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    });
+    let presented = bearer.or(query).unwrap_or_default();
+    if state.token.is_empty() || !constant_time_eq(presented.as_bytes(), state.token.as_bytes()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Runs `call` on a blocking thread. `None` means it panicked.
+async fn blocking<T: Send + 'static>(
+    state: &ServerState,
+    call: impl FnOnce(&dyn Controller) -> T + Send + 'static,
+) -> Option<T> {
+    let controller = state.controller.clone();
+    tauri::async_runtime::spawn_blocking(move || call(controller.as_ref()))
+        .await
+        .ok()
+}
+
+async fn status(State(state): State<ServerState>) -> Response {
+    match blocking(&state, |controller| controller.status()).await {
+        Some(snapshot) => Json(snapshot).into_response(),
+        None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn apply(State(state): State<ServerState>, control: Control) -> Response {
+    match blocking(&state, move |controller| controller.apply(control)).await {
+        Some(Ok(snapshot)) => Json(snapshot).into_response(),
+        Some(Err(err)) => (StatusCode::BAD_REQUEST, err).into_response(),
+        None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct SeekBody {
+    progress: f64,
+}
+
+async fn seek(state: State<ServerState>, Json(body): Json<SeekBody>) -> Response {
+    apply(
+        state,
+        Control::Seek {
+            progress: body.progress,
+        },
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+struct NudgeBody {
+    seconds: f64,
+}
+
+async fn nudge(state: State<ServerState>, Json(body): Json<NudgeBody>) -> Response {
+    apply(
+        state,
+        Control::Nudge {
+            seconds: body.seconds,
+        },
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+struct SpeedBody {
+    rate: f64,
+}
+
+async fn speed(state: State<ServerState>, Json(body): Json<SpeedBody>) -> Response {
+    apply(state, Control::Speed { rate: body.rate }).await
+}
+
+async fn socket(State(state): State<ServerState>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| stream(socket, state))
+}
+
+/// Sends the current snapshot, then each published one that differs from
+/// the last sent, and applies incoming controls.
+async fn stream(mut socket: WebSocket, state: ServerState) {
+    let mut updates = state.updates.subscribe();
+    let mut pending = blocking(&state, |controller| controller.status()).await;
+    let mut last_sent = String::new();
+    loop {
+        if let Some(json) = pending
+            .take()
+            .and_then(|snapshot| serde_json::to_string(&snapshot).ok())
+        {
+            if json != last_sent {
+                if socket
+                    .send(Message::Text(json.clone().into()))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+                last_sent = json;
+            }
+        }
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(snapshot) => pending = Some(snapshot),
+                Err(RecvError::Lagged(_)) => {
+                    pending = blocking(&state, |controller| controller.status()).await;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    if let Ok(control) = serde_json::from_str::<Control>(&text) {
+                        blocking(&state, move |controller| {
+                            let _ = controller.apply(control);
+                        })
+                        .await;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 16];
+    if getrandom::fill(&mut bytes).is_err() {
+        return String::new();
+    }
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Remote server owned by the app: persisted config plus the running instance.
+pub struct RemoteServer {
+    path: PathBuf,
+    controller: Arc<dyn Controller>,
+    pairing: Arc<Pairing>,
+    updates: broadcast::Sender<PlaybackSnapshot>,
+    config: Mutex<RemoteConfig>,
+    running: Mutex<Option<RunningServer>>,
+}
+
+impl RemoteServer {
+    pub fn load(path: PathBuf, controller: Arc<dyn Controller>) -> Self {
+        let mut config: RemoteConfig = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        let needs_token = config.token.is_empty();
+        if needs_token {
+            config.token = generate_token();
+        }
+        let server = Self {
+            path,
+            updates: snapshot_updates(controller.as_ref()),
+            controller,
+            pairing: Arc::new(Pairing::default()),
+            config: Mutex::new(config),
+            running: Mutex::new(None),
+        };
+        if needs_token {
+            let _ = server.save();
+        }
+        server
+    }
+
+    pub fn status(&self) -> RemoteStatus {
+        let config = self.config().clone();
+        let address = self
+            .running
+            .lock()
+            .ok()
+            .and_then(|running| running.as_ref().map(|server| server.address.to_string()));
+        RemoteStatus { config, address }
+    }
+
+    /// Starts or stops the server to match the current config.
+    pub fn apply(&self) -> Result<RemoteStatus, String> {
+        let config = self.config().clone();
+        let mut running = self.running.lock().map_err(|e| e.to_string())?;
+        if let Some(server) = running.take() {
+            server.stop();
+        }
+        if config.enabled {
+            *running = Some(start(
+                &config,
+                self.controller.clone(),
+                self.pairing.clone(),
+                self.updates.clone(),
+            )?);
+        }
+        drop(running);
+        Ok(self.status())
+    }
+
+    pub fn update(
+        &self,
+        enabled: bool,
+        bind: RemoteBind,
+        port: u16,
+    ) -> Result<RemoteStatus, String> {
+        {
+            let mut config = self.config();
+            config.enabled = enabled;
+            config.bind = bind;
+            config.port = port;
+        }
+        self.save()?;
+        self.apply()
+    }
+
+    /// Issues a fresh pairing code and the QR link that carries it.
+    pub fn pair(&self) -> Result<RemotePairing, String> {
+        let port = self
+            .running
+            .lock()
+            .map_err(|e| e.to_string())?
+            .as_ref()
+            .map(|server| server.address.port())
+            .ok_or("remote server is not running")?;
+        let host = match self.config().bind {
+            RemoteBind::Lan => lan_ip().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            RemoteBind::Localhost => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        };
+        let code = self.pairing.issue()?;
+        let url = format!("http://{host}:{port}/?code={code}");
+        let qr_svg = QrCode::new(&url)
+            .map_err(|e| e.to_string())?
+            .render::<svg::Color>()
+            .min_dimensions(200, 200)
+            .build();
+        Ok(RemotePairing { url, code, qr_svg })
+    }
+
+    pub fn regenerate_token(&self) -> Result<RemoteStatus, String> {
+        self.config().token = generate_token();
+        self.save()?;
+        self.apply()
+    }
+
+    fn config(&self) -> std::sync::MutexGuard<'_, RemoteConfig> {
+        self.config
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn save(&self) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(&*self.config()).map_err(|e| e.to_string())?;
+        fs::write(&self.path, json).map_err(|e| e.to_string())
+    }
+}
+
+#[tauri::command]
+pub fn remote_status(server: tauri::State<'_, RemoteServer>) -> RemoteStatus {
+    server.status()
+}
+
+#[tauri::command]
+pub fn update_remote(
+    server: tauri::State<'_, RemoteServer>,
+    enabled: bool,
+    bind: RemoteBind,
+    port: u16,
+) -> Result<RemoteStatus, String> {
+    server.update(enabled, bind, port)
+}
+
+/// Address phones on the same network can reach: the one used for outbound
+/// traffic, or, without a default route (an offline studio network), the
+/// best address among the interfaces that are up.
+fn lan_ip() -> Option<IpAddr> {
+    routed_ip().or_else(|| {
+        let interfaces = if_addrs::get_if_addrs().ok()?;
+        let addresses = interfaces
+            .iter()
+            .filter(|interface| interface.is_oper_up() && !interface.is_p2p())
+            .filter_map(|interface| match interface.ip() {
+                IpAddr::V4(ip) => Some(ip),
+                IpAddr::V6(_) => None,
+            });
+        preferred_lan_ip(addresses).map(IpAddr::V4)
+    })
+}
+
+/// Address of the interface used for outbound traffic. Connecting a UDP
+/// socket only selects a route; no packet is sent.
+fn routed_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80)).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// Prefers a private address over any other usable one. Loopback and
+/// link-local addresses are not reachable from another device.
+fn preferred_lan_ip(addresses: impl IntoIterator<Item = Ipv4Addr>) -> Option<Ipv4Addr> {
+    addresses
+        .into_iter()
+        .filter(|ip| !ip.is_loopback() && !ip.is_link_local() && !ip.is_unspecified())
+        .min_by_key(|ip| !ip.is_private())
+}
+
+#[tauri::command]
+pub fn remote_pairing(server: tauri::State<'_, RemoteServer>) -> Result<RemotePairing, String> {
+    server.pair()
+}
+
+#[tauri::command]
+pub fn regenerate_remote_token(
+    server: tauri::State<'_, RemoteServer>,
+) -> Result<RemoteStatus, String> {
+    server.regenerate_token()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playback::{Playback, PlaybackState, SystemClock};
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_tungstenite::tungstenite;
+
+    const TOKEN: &str = "secret";
+
+    fn serve() -> (Playback, Arc<Pairing>, RunningServer) {
+        let playback = Playback::new(Arc::new(SystemClock::new()));
+        playback.load("one two three four five six seven eight nine ten");
+        let config = RemoteConfig {
+            enabled: true,
+            port: 0,
+            token: TOKEN.to_string(),
+            ..RemoteConfig::default()
+        };
+        let pairing = Arc::new(Pairing::default());
+        let updates = snapshot_updates(&playback);
+        let server = start(
+            &config,
+            Arc::new(playback.clone()),
+            pairing.clone(),
+            updates,
+        )
+        .unwrap();
+        (playback, pairing, server)
+    }
+
+    async fn request(address: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        send(
+            address,
+            method,
+            path,
+            &format!("Authorization: Bearer {TOKEN}\r\n"),
+            body,
+        )
+        .await
+    }
+
+    async fn send(
+        address: SocketAddr,
+        method: &str,
+        path: &str,
+        headers: &str,
+        body: &str,
+    ) -> (u16, String) {
+        let mut stream = tokio::net::TcpStream::connect(address).await.unwrap();
+        let request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\n{headers}\
+             Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_token() {
+        let (_playback, _pairing, server) = serve();
+        let (status, _) = send(server.address, "GET", "/api/status", "", "").await;
+        assert_eq!(status, 401);
+        let (status, _) = send(server.address, "GET", "/api/status?token=wrong", "", "").await;
+        assert_eq!(status, 401);
+        server.stop();
+    }
+
+    #[tokio::test]
+    async fn pairing_code_is_single_use() {
+        let (_playback, pairing, server) = serve();
+        let (status, body) = send(server.address, "GET", "/", "", "").await;
+        assert_eq!(status, 200);
+        assert!(body.contains("<html"));
+
+        let code = pairing.issue().unwrap();
+        let wrong = if code == "000000" { "111111" } else { "000000" };
+        let (status, _) = send(
+            server.address,
+            "POST",
+            "/api/pair",
+            "",
+            &format!(r#"{{"code":"{wrong}"}}"#),
+        )
+        .await;
+        assert_eq!(status, 401);
+
+        let body = format!(r#"{{"code":"{code}"}}"#);
+        let (status, response) = send(server.address, "POST", "/api/pair", "", &body).await;
+        assert_eq!(status, 200);
+        assert!(response.contains(TOKEN), "{response}");
+
+        let (status, _) = send(server.address, "POST", "/api/pair", "", &body).await;
+        assert_eq!(status, 401);
+        server.stop();
+    }
+
+    #[test]
+    fn pairing_code_expires_after_failed_attempts() {
+        let pairing = Pairing::default();
+        let code = pairing.issue().unwrap();
+        for _ in 0..PAIRING_ATTEMPTS {
+            assert!(!pairing.redeem("not-a-code"));
+        }
+        assert!(!pairing.redeem(&code));
+    }
+
+    #[test]
+    fn no_pairing_code_without_random_bytes() {
+        let pairing = Pairing::default();
+        let code = pairing.issue().unwrap();
+        assert!(pairing.issue_from(Err("no entropy".to_string())).is_err());
+        assert!(!pairing.redeem(&code));
+        assert!(!pairing.redeem("000000"));
+        assert_eq!(pairing.issue_from(Ok([7, 0, 0, 0])).unwrap(), "000007");
+    }
+
+    #[test]
+    fn prefers_private_interface_addresses() {
+        let ip = |a, b, c, d| Ipv4Addr::new(a, b, c, d);
+        let addresses = [
+            ip(127, 0, 0, 1),
+            ip(169, 254, 3, 4),
+            ip(100, 64, 0, 7),
+            ip(192, 168, 1, 20),
+            ip(10, 0, 0, 5),
+        ];
+        assert_eq!(preferred_lan_ip(addresses), Some(ip(192, 168, 1, 20)));
+        assert_eq!(
+            preferred_lan_ip(addresses[..3].iter().copied()),
+            Some(ip(100, 64, 0, 7))
+        );
+        assert_eq!(preferred_lan_ip(addresses[..2].iter().copied()), None);
+    }
+
+    #[tokio::test]
+    async fn controls_playback_over_http() {
+        let (playback, _pairing, server) = serve();
+
+        let (status, _) = request(server.address, "POST", "/api/play", "").await;
+        assert_eq!(status, 200);
+        assert_eq!(playback.snapshot().state, PlaybackState::Playing);
+        request(server.address, "POST", "/api/pause", "").await;
+
+        let (status, body) =
+            request(server.address, "POST", "/api/seek", r#"{"progress":0.5}"#).await;
+        assert_eq!(status, 200);
+        assert!(body.contains("\"progress\":0.5"), "{body}");
+
+        let (_, body) = request(server.address, "POST", "/api/nudge", r#"{"seconds":-1}"#).await;
+        assert!(body.contains("\"elapsedSeconds\":4.0"), "{body}");
+
+        request(server.address, "POST", "/api/speed", r#"{"rate":120}"#).await;
+        let (_, body) = request(server.address, "GET", "/api/status", "").await;
+        assert!(body.contains("\"state\":\"paused\""), "{body}");
+        assert!(body.contains("\"rate\":120.0"), "{body}");
+
+        request(server.address, "POST", "/api/stop", "").await;
+        assert_eq!(playback.snapshot().state, PlaybackState::Idle);
+        server.stop();
+    }
+
+    #[tokio::test]
+    async fn streams_position_over_websocket() {
+        let (_playback, _pairing, server) = serve();
+        let url = format!("ws://{}/api/ws?token={TOKEN}", server.address);
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let first = socket.next().await.unwrap().unwrap();
+        assert!(first.to_text().unwrap().contains("\"state\":\"idle\""));
+
+        socket
+            .send(tungstenite::Message::text(r#"{"action":"play"}"#))
+            .await
+            .unwrap();
+        loop {
+            let message = socket.next().await.unwrap().unwrap();
+            if message.to_text().unwrap().contains("\"state\":\"playing\"") {
+                break;
+            }
+        }
+        server.stop();
+    }
+
+    #[tokio::test]
+    async fn pushes_changes_made_elsewhere() {
+        let (playback, _pairing, server) = serve();
+        let url = format!("ws://{}/api/ws?token={TOKEN}", server.address);
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        socket.next().await.unwrap().unwrap();
+
+        playback.seek(0.5);
+        let message = socket.next().await.unwrap().unwrap();
+        assert!(message.to_text().unwrap().contains("\"progress\":0.5"));
+        server.stop();
+    }
+}
@@ -0,0 +1,259 @@
+//! Reading script files from disk: plain text, Markdown and the app's own
+//! `.fpscript` files, which hold a library script as JSON. Markdown, Word,
+//! OpenDocument and subtitle files are converted to script markup on the way
+//! in.
+//!
+//! Many scripts are written in Chinese Windows editors, so text is not
+//! assumed to be UTF-8. A byte-order mark wins. Otherwise text that decodes
+//! to well-formed, mostly common characters as UTF-16 is read as BOM-less
+//! UTF-16 if it has NUL bytes or is not valid UTF-8 (UTF-16 of ASCII text is
+//! valid UTF-8 full of NULs), valid UTF-8 is taken as is, and anything else
+//! is decoded as GBK (GB18030).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use encoding_rs::{Encoding, GB18030, UTF_16BE, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::import::{docx, markdown, odt, subtitles};
+
+pub const NATIVE_EXTENSION: &str = "fpscript";
+pub const EXTENSIONS: [&str; 8] = [
+    "txt",
+    "md",
+    "markdown",
+    "docx",
+    "odt",
+    "srt",
+    "vtt",
+    NATIVE_EXTENSION,
+];
+
+/// A script file read from disk.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenedFile {
+    pub path: PathBuf,
+    pub title: String,
+    pub text: String,
+    /// Name of the encoding the file was decoded with.
+    pub encoding: String,
+}
+
+#[derive(Deserialize)]
+struct NativeScript {
+    #[serde(default)]
+    title: String,
+    body: String,
+}
+
+/// Characters scripts are mostly made of: ASCII, CJK punctuation, kana,
+/// ideographs and fullwidth forms. Hangul is left out because GBK byte pairs
+/// read as UTF-16 land there.
+fn is_common(c: char) -> bool {
+    c.is_ascii()
+        || matches!(
+            c,
+            '\u{3000}'..='\u{30FF}'
+                | '\u{4E00}'..='\u{9FFF}'
+                | '\u{FF00}'..='\u{FFEF}'
+        )
+}
+
+/// How many common characters `bytes` holds as UTF-16, or `None` if they
+/// are not plausible UTF-16 text: an unpaired surrogate, a control character
+/// other than a line break or tab, or a private-use character rules it out,
+/// and so does having fewer than nine in ten common characters.
+fn utf16_score(bytes: &[u8], little_endian: bool) -> Option<usize> {
+    let units = bytes.chunks_exact(2).map(|pair| {
+        let pair = [pair[0], pair[1]];
+        if little_endian {
+            u16::from_le_bytes(pair)
+        } else {
+            u16::from_be_bytes(pair)
+        }
+    });
+    let (mut total, mut common) = (0, 0);
+    for c in char::decode_utf16(units) {
+        let c = c.ok()?;
+        if (c.is_control() && !matches!(c, '\t' | '\n' | '\r'))
+            || matches!(c, '\u{E000}'..='\u{F8FF}' | '\u{FFF0}'..='\u{FFFF}')
+        {
+            return None;
+        }
+        total += 1;
+        common += usize::from(is_common(c));
+    }
+    (common * 10 >= total * 9).then_some(common)
+}
+
+fn looks_like_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    match (utf16_score(bytes, true), utf16_score(bytes, false)) {
+        (Some(le), Some(be)) if be > le => Some(UTF_16BE),
+        (Some(_), _) => Some(UTF_16LE),
+        (None, Some(_)) => Some(UTF_16BE),
+        (None, None) => None,
+    }
+}
+
+/// Decodes `bytes`, returning the text and the encoding that was used.
+pub fn decode(bytes: &[u8]) -> (String, &'static Encoding) {
+    let encoding = if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        encoding
+    } else if let Some(encoding) = looks_like_utf16(bytes)
+        .filter(|_| bytes.contains(&0) || std::str::from_utf8(bytes).is_err())
+    {
+        encoding
+    } else if std::str::from_utf8(bytes).is_ok() {
+        UTF_8
+    } else {
+        GB18030
+    };
+    let (text, encoding, _) = encoding.decode(bytes);
+    (text.into_owned(), encoding)
+}
+
+pub fn read(path: &Path) -> Result<OpenedFile, String> {
+    let bytes = fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let document = match extension.as_str() {
+        "docx" => Some(docx::to_script(&bytes)),
+        "odt" => Some(odt::to_script(&bytes)),
+        _ => None,
+    };
+    if let Some(text) = document {
+        return Ok(OpenedFile {
+            path: path.to_path_buf(),
+            title: stem,
+            text: text.map_err(|e| format!("{}: {e}", path.display()))?,
+            encoding: UTF_8.name().to_string(),
+        });
+    }
+    let (text, encoding) = decode(&bytes);
+    let (title, text) = if extension == NATIVE_EXTENSION {
+        let script: NativeScript = serde_json::from_str(&text)
+            .map_err(|e| format!("{}: not a script file: {e}", path.display()))?;
+        let title = if script.title.trim().is_empty() {
+            stem
+        } else {
+            script.title
+        };
+        (title, script.body)
+    } else if matches!(extension.as_str(), "md" | "markdown") {
+        (stem, markdown::to_script(&text))
+    } else if matches!(extension.as_str(), "srt" | "vtt") {
+        let text = subtitles::to_script(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+        (stem, text)
+    } else {
+        (stem, text)
+    };
+    Ok(OpenedFile {
+        path: path.to_path_buf(),
+        title,
+        text,
+        encoding: encoding.name().to_string(),
+    })
+}
+
+/// Asks for a script file and reads it. Returns `None` when the dialog was
+/// cancelled.
+#[tauri::command]
+pub async fn open_script_file(app: AppHandle) -> Result<Option<OpenedFile>, String> {
+    let Some(path) = app
+        .dialog()
+        .file()
+        .add_filter("Scripts", &EXTENSIONS)
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    read(&path).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_common_encodings() {
+        let text = "大家好，Hello";
+        let mut bom = vec![0xEF, 0xBB, 0xBF];
+        bom.extend_from_slice(text.as_bytes());
+        let (gbk, _, _) = GB18030.encode(text);
+        let utf16le: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut utf16be_bom = vec![0xFE, 0xFF];
+        utf16be_bom.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+
+        assert_eq!(decode(text.as_bytes()), (text.to_string(), UTF_8));
+        assert_eq!(decode(&bom), (text.to_string(), UTF_8));
+        assert_eq!(decode(&gbk), (text.to_string(), GB18030));
+        assert_eq!(decode(&utf16le), (text.to_string(), UTF_16LE));
+        assert_eq!(decode(&utf16be_bom), (text.to_string(), UTF_16BE));
+    }
+
+    #[test]
+    fn detects_bom_less_utf16() {
+        let text = "大家好，欢迎收看今天的节目。\r\n「こんにちは」";
+        let utf16le: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let utf16be: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        assert_eq!(decode(&utf16le), (text.to_string(), UTF_16LE));
+        assert_eq!(decode(&utf16be), (text.to_string(), UTF_16BE));
+
+        // GBK with an even number of bytes stays GBK.
+        for text in [
+            "大家好，欢迎收看今天的节目。",
+            "第一场 Take 12：开机！",
+            "谢谢大家",
+        ] {
+            let (gbk, _, _) = GB18030.encode(text);
+            assert_eq!(gbk.len() % 2, 0);
+            assert_eq!(decode(&gbk), (text.to_string(), GB18030));
+        }
+        // UTF-16 of ASCII text is also valid UTF-8.
+        let text = "Hello, world!\r\nLine two\tends here.";
+        let utf16le: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let utf16be: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        assert_eq!(decode(&utf16le), (text.to_string(), UTF_16LE));
+        assert_eq!(decode(&utf16be), (text.to_string(), UTF_16BE));
+        assert_eq!(decode(b"Hi\0!"), ("Hi\0!".to_string(), UTF_8));
+
+        // A lone surrogate is not UTF-16.
+        assert_eq!(looks_like_utf16(&[0x00, 0xD8, 0x41, 0x00]), None);
+    }
+
+    #[test]
+    fn reads_native_scripts_and_titles_plain_files() {
+        let dir = std::env::temp_dir().join(format!("flash-prompter-file-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let native = dir.join("launch.fpscript");
+        fs::write(&native, r#"{"title":"Launch","body":"第一行\n第二行"}"#).unwrap();
+        let plain = dir.join("notes.txt");
+        fs::write(&plain, "Hello").unwrap();
+
+        let opened = read(&native).unwrap();
+        assert_eq!(
+            (opened.title.as_str(), opened.text.as_str()),
+            ("Launch", "第一行\n第二行")
+        );
+        let opened = read(&plain).unwrap();
+        assert_eq!(
+            (opened.title.as_str(), opened.text.as_str()),
+            ("notes", "Hello")
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+}
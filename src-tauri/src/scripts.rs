@@ -0,0 +1,384 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+const SCRIPT_EXTENSION: &str = "json";
+/// Asks the editor to show a script, carrying the whole `Script`.
+pub const OPEN_EVENT: &str = "scripts://open";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Script {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: u64,
+    pub modified_at: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptSummary {
+    pub id: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub created_at: u64,
+    pub modified_at: u64,
+}
+
+impl From<&Script> for ScriptSummary {
+    fn from(script: &Script) -> Self {
+        Self {
+            id: script.id.clone(),
+            title: script.title.clone(),
+            tags: script.tags.clone(),
+            created_at: script.created_at,
+            modified_at: script.modified_at,
+        }
+    }
+}
+
+/// A script as sent by the editor. Saving without an `id` creates a new script.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptDraft {
+    pub id: Option<String>,
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Scripts stored as one JSON file per script under the app data dir.
+pub struct ScriptLibrary {
+    dir: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl ScriptLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<ScriptSummary>, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.to_string()),
+        };
+        let mut scripts: Vec<ScriptSummary> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == SCRIPT_EXTENSION))
+            .filter_map(|path| read_script(&path).ok())
+            .map(|script| ScriptSummary::from(&script))
+            .collect();
+        scripts.sort_by_key(|script| std::cmp::Reverse(script.modified_at));
+        Ok(scripts)
+    }
+
+    pub fn load(&self, id: &str) -> Result<Script, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        read_script(&self.path_for(id)?)
+    }
+
+    pub fn save(&self, draft: ScriptDraft) -> Result<Script, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        let now = now_millis();
+        let existing = match &draft.id {
+            Some(id) => Some(read_script(&self.path_for(id)?)?),
+            None => None,
+        };
+        let script = match existing {
+            Some(existing) => Script {
+                title: normalize_title(&draft.title),
+                body: draft.body,
+                tags: normalize_tags(draft.tags),
+                modified_at: now,
+                ..existing
+            },
+            None => Script {
+                id: self.unique_id(&draft.title),
+                title: normalize_title(&draft.title),
+                body: draft.body,
+                tags: normalize_tags(draft.tags),
+                created_at: now,
+                modified_at: now,
+            },
+        };
+        self.write(&script)?;
+        Ok(script)
+    }
+
+    /// Changes the display title. The id is kept so existing references stay valid.
+    pub fn rename(&self, id: &str, title: &str) -> Result<Script, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        let mut script = read_script(&self.path_for(id)?)?;
+        script.title = normalize_title(title);
+        script.modified_at = now_millis();
+        self.write(&script)?;
+        Ok(script)
+    }
+
+    pub fn duplicate(&self, id: &str) -> Result<Script, String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        let source = read_script(&self.path_for(id)?)?;
+        let title = format!("{} copy", source.title);
+        let now = now_millis();
+        let script = Script {
+            id: self.unique_id(&title),
+            title,
+            created_at: now,
+            modified_at: now,
+            ..source
+        };
+        self.write(&script)?;
+        Ok(script)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let _guard = self.lock.lock().map_err(|e| e.to_string())?;
+        fs::remove_file(self.path_for(id)?).map_err(|e| e.to_string())
+    }
+
+    fn path_for(&self, id: &str) -> Result<PathBuf, String> {
+        let valid = !id.is_empty() && id.chars().all(|c| c.is_alphanumeric() || c == '-');
+        if !valid {
+            return Err(format!("invalid script id: {id}"));
+        }
+        Ok(self.dir.join(format!("{id}.{SCRIPT_EXTENSION}")))
+    }
+
+    fn unique_id(&self, title: &str) -> String {
+        let base = slugify(title);
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while self.dir.join(format!("{id}.{SCRIPT_EXTENSION}")).exists() {
+            id = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+        id
+    }
+
+    fn write(&self, script: &Script) -> Result<(), String> {
+        fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        let path = self.path_for(&script.id)?;
+        let tmp = path.with_extension("tmp");
+        let json = serde_json::to_string_pretty(script).map_err(|e| e.to_string())?;
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &path).map_err(|e| e.to_string())
+    }
+}
+
+fn read_script(path: &Path) -> Result<Script, String> {
+    let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw).map_err(|e| e.to_string())
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn normalize_title(title: &str) -> String {
+    let title = title.trim();
+    if title.is_empty() {
+        "Untitled".to_string()
+    } else {
+        title.to_string()
+    }
+}
+
+fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Lowercases the title and collapses everything that isn't alphanumeric into
+/// single dashes. CJK titles are kept as-is since they are valid file names.
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "script".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Shows `script` in the editor of the main window and brings it forward.
+pub fn open_in_editor(app: &AppHandle, script: Script) {
+    let _ = app.emit_to("main", OPEN_EVENT, script);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+#[tauri::command]
+pub fn list_scripts(library: State<'_, ScriptLibrary>) -> Result<Vec<ScriptSummary>, String> {
+    library.list()
+}
+
+#[tauri::command]
+pub fn load_script(library: State<'_, ScriptLibrary>, id: String) -> Result<Script, String> {
+    library.load(&id)
+}
+
+#[tauri::command]
+pub fn save_script(
+    library: State<'_, ScriptLibrary>,
+    script: ScriptDraft,
+) -> Result<Script, String> {
+    library.save(script)
+}
+
+#[tauri::command]
+pub fn rename_script(
+    library: State<'_, ScriptLibrary>,
+    id: String,
+    title: String,
+) -> Result<Script, String> {
+    library.rename(&id, &title)
+}
+
+#[tauri::command]
+pub fn duplicate_script(library: State<'_, ScriptLibrary>, id: String) -> Result<Script, String> {
+    library.duplicate(&id)
+}
+
+#[tauri::command]
+pub fn delete_script(library: State<'_, ScriptLibrary>, id: String) -> Result<(), String> {
+    library.delete(&id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn library(name: &str) -> ScriptLibrary {
+        let dir = std::env::temp_dir().join(format!(
+            "flash-prompter-scripts-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        ScriptLibrary::new(dir)
+    }
+
+    fn draft(id: Option<&str>, title: &str, body: &str) -> ScriptDraft {
+        ScriptDraft {
+            id: id.map(String::from),
+            title: title.to_string(),
+            body: body.to_string(),
+            tags: vec![" news ".to_string(), "live".to_string(), "news".to_string()],
+        }
+    }
+
+    #[test]
+    fn rejects_ids_that_are_not_plain_names() {
+        let library = library("ids");
+        for id in ["", "../settings", "a/b", "a.json", "a b"] {
+            assert!(library.path_for(id).is_err(), "{id}");
+            assert!(library.load(id).is_err(), "{id}");
+            assert!(library.delete(id).is_err(), "{id}");
+        }
+        assert_eq!(
+            library.path_for("早间新闻-2").unwrap(),
+            library.dir.join("早间新闻-2.json")
+        );
+    }
+
+    #[test]
+    fn saves_renames_duplicates_and_deletes() {
+        let library = library("crud");
+        let first = library
+            .save(draft(None, "  Morning Show! ", "第一行"))
+            .unwrap();
+        assert_eq!(first.id, "morning-show");
+        assert_eq!(first.title, "Morning Show!");
+        assert_eq!(first.tags, ["live", "news"]);
+        assert_eq!(
+            library.save(draft(None, "Morning show", "")).unwrap().id,
+            "morning-show-2"
+        );
+        assert_eq!(
+            library.save(draft(None, " ", "")).unwrap().title,
+            "Untitled"
+        );
+
+        let updated = library
+            .save(draft(Some("morning-show"), "Evening", "第二行"))
+            .unwrap();
+        assert_eq!(updated.id, "morning-show");
+        assert_eq!(updated.created_at, first.created_at);
+        assert_eq!(library.load("morning-show").unwrap().body, "第二行");
+        assert!(library.save(draft(Some("missing"), "x", "")).is_err());
+
+        let renamed = library.rename("morning-show", "晚间新闻").unwrap();
+        assert_eq!(
+            (renamed.id.as_str(), renamed.title.as_str()),
+            ("morning-show", "晚间新闻")
+        );
+
+        let copy = library.duplicate("morning-show").unwrap();
+        assert_eq!(
+            (copy.id.as_str(), copy.title.as_str()),
+            ("晚间新闻-copy", "晚间新闻 copy")
+        );
+        assert_eq!(copy.body, "第二行");
+
+        library.delete("morning-show").unwrap();
+        assert!(library.load("morning-show").is_err());
+        assert!(library.delete("morning-show").is_err());
+        assert_eq!(library.list().unwrap().len(), 3);
+        let _ = fs::remove_dir_all(&library.dir);
+    }
+
+    #[test]
+    fn lists_the_most_recently_modified_first() {
+        let library = library("list");
+        assert!(library.list().unwrap().is_empty());
+        for (id, modified_at) in [("a", 1), ("b", 3), ("c", 2)] {
+            library
+                .write(&Script {
+                    id: id.to_string(),
+                    title: id.to_string(),
+                    body: String::new(),
+                    tags: Vec::new(),
+                    created_at: 0,
+                    modified_at,
+                })
+                .unwrap();
+        }
+        fs::write(library.dir.join("broken.json"), "{").unwrap();
+        fs::write(library.dir.join("notes.txt"), "not a script").unwrap();
+        let ids: Vec<String> = library.list().unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, ["b", "c", "a"]);
+        let _ = fs::remove_dir_all(&library.dir);
+    }
+}
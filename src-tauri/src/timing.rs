@@ -0,0 +1,317 @@
+use serde::Serialize;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::markup::{self, Document, Inline};
+
+/// Extra time after a sentence-ending mark such as `。` or `?`.
+const SENTENCE_PAUSE: f64 = 0.5;
+/// Extra time after a clause mark such as `，` or `;`.
+const CLAUSE_PAUSE: f64 = 0.25;
+/// Extra time for each blank line separating paragraphs.
+const PARAGRAPH_PAUSE: f64 = 0.6;
+const MIN_RATE: f64 = 1.0;
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineTiming {
+    pub line: usize,
+    /// Reading units: one per Latin word or number, one per CJK character.
+    pub units: f64,
+    pub pause_seconds: f64,
+    pub start_seconds: f64,
+    pub seconds: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingEstimate {
+    /// Units read per minute.
+    pub rate: f64,
+    pub word_count: usize,
+    pub ideograph_count: usize,
+    pub total_units: f64,
+    pub total_seconds: f64,
+    pub lines: Vec<LineTiming>,
+    /// Whether line times come from `[at ...]` cues rather than the rate.
+    pub timed: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct LineUnits {
+    words: usize,
+    ideographs: usize,
+    pause_seconds: f64,
+}
+
+impl LineUnits {
+    fn units(&self) -> f64 {
+        (self.words + self.ideographs) as f64
+    }
+}
+
+/// Segments mixed CJK/Latin text by Unicode word boundaries. Each ideograph,
+/// kana or hangul syllable is its own unit; other words count once.
+/// Runs of punctuation contribute their longest pause.
+fn measure_line(text: &str) -> LineUnits {
+    let mut measured = LineUnits::default();
+    let mut pending_pause: f64 = 0.0;
+    for segment in text.split_word_bounds() {
+        let ideographs = segment.chars().filter(|c| is_cjk(*c)).count();
+        if ideographs > 0 {
+            measured.ideographs += ideographs;
+        } else if segment.chars().any(char::is_alphanumeric) {
+            measured.words += 1;
+        } else {
+            pending_pause =
+                pending_pause.max(segment.chars().map(punctuation_pause).fold(0.0, f64::max));
+            continue;
+        }
+        measured.pause_seconds += pending_pause;
+        pending_pause = 0.0;
+    }
+    measured.pause_seconds += pending_pause;
+    measured
+}
+
+pub fn estimate(text: &str, rate: f64) -> TimingEstimate {
+    estimate_document(&markup::parse(text).document, rate)
+}
+
+/// Times a parsed script. Speed cues scale the rate until the next speed cue,
+/// pause cues add their duration and notes are not read.
+pub fn estimate_document(document: &Document, rate: f64) -> TimingEstimate {
+    let rate = rate.max(MIN_RATE);
+    let mut estimate = TimingEstimate {
+        rate,
+        ..TimingEstimate::default()
+    };
+    let mut speed = 1.0;
+    for (index, line) in document.lines.iter().enumerate() {
+        let mut units = 0.0;
+        let mut spoken_units = 0.0;
+        let mut pause_seconds = 0.0;
+        for inline in &line.inlines {
+            match inline {
+                Inline::Text { text } | Inline::Emphasis { text } => {
+                    let measured = measure_line(text);
+                    estimate.word_count += measured.words;
+                    estimate.ideograph_count += measured.ideographs;
+                    units += measured.units();
+                    spoken_units += measured.units() / speed;
+                    pause_seconds += measured.pause_seconds;
+                }
+                Inline::Pause { seconds } => pause_seconds += seconds,
+                Inline::Speed { factor } => speed = *factor,
+                Inline::Note { .. } | Inline::Section { .. } | Inline::Time { .. } => {}
+            }
+        }
+        if is_blank(&line.inlines) {
+            pause_seconds = PARAGRAPH_PAUSE;
+        }
+        let seconds = spoken_units * 60.0 / rate + pause_seconds;
+        estimate.total_units += units;
+        estimate.lines.push(LineTiming {
+            line: index,
+            units,
+            pause_seconds,
+            start_seconds: estimate.total_seconds,
+            seconds,
+        });
+        estimate.total_seconds += seconds;
+    }
+    apply_cue_times(document, &mut estimate);
+    estimate
+}
+
+/// Replaces the estimated line times with cue times when the script has
+/// `[at ...]` cues, e.g. imported subtitles. The first cue on a line counts.
+/// A cue's span is shared by its line and the uncued lines after it in
+/// proportion to their reading time, and the gap until the next cue is held
+/// on the last of them.
+fn apply_cue_times(document: &Document, estimate: &mut TimingEstimate) {
+    let cues: Vec<(usize, f64, Option<f64>)> = document
+        .lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            line.inlines.iter().find_map(|inline| match inline {
+                Inline::Time { start, end } => Some((index, *start, *end)),
+                _ => None,
+            })
+        })
+        .collect();
+    let Some(&(first_cued, first_start, _)) = cues.first() else {
+        return;
+    };
+    estimate.timed = true;
+    let mut groups = Vec::with_capacity(cues.len() + 1);
+    if first_cued > 0 {
+        // Lines before the first cue lead up to it.
+        groups.push((0, 0.0, Some(first_start)));
+    }
+    groups.extend(cues);
+
+    let mut cursor: f64 = 0.0;
+    for (index, &(first, start, end)) in groups.iter().enumerate() {
+        let next = groups.get(index + 1);
+        let lines = &mut estimate.lines[first..next.map_or(document.lines.len(), |next| next.0)];
+        let reading: f64 = lines
+            .iter()
+            .map(|line| line.seconds - line.pause_seconds)
+            .sum();
+        let start = start.max(cursor);
+        let next_start = next.map(|next| next.1.max(start));
+        let end = end
+            .or(next_start)
+            .unwrap_or(start + reading)
+            .max(start)
+            .min(next_start.unwrap_or(f64::INFINITY));
+        let hold = next_start.map_or(0.0, |next_start| next_start - end);
+        let mut line_start = start;
+        for (offset, line) in lines.iter_mut().enumerate() {
+            let share = if reading > 0.0 {
+                (line.seconds - line.pause_seconds) / reading
+            } else if offset == 0 {
+                1.0
+            } else {
+                0.0
+            };
+            line.start_seconds = line_start;
+            line.seconds = (end - start) * share;
+            line.pause_seconds = 0.0;
+            line_start += line.seconds;
+        }
+        if let Some(line) = lines.last_mut() {
+            line.pause_seconds = hold;
+            line.seconds += hold;
+        }
+        cursor = end + hold;
+    }
+    estimate.total_seconds = cursor;
+}
+
+fn is_blank(inlines: &[Inline]) -> bool {
+    inlines
+        .iter()
+        .all(|inline| matches!(inline, Inline::Text { text } if text.trim().is_empty()))
+}
+
+fn punctuation_pause(c: char) -> f64 {
+    match c {
+        '.' | '!' | '?' | '…' | '。' | '！' | '？' => SENTENCE_PAUSE,
+        ',' | ';' | ':' | '—' | '，' | '、' | '；' | '：' => CLAUSE_PAUSE,
+        _ => 0.0,
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+#[tauri::command]
+pub fn estimate_timing(text: String, rate: f64) -> TimingEstimate {
+    estimate(&text, rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    /// `(start_seconds, seconds)` of each line.
+    fn assert_lines(estimate: &TimingEstimate, expected: &[(f64, f64)]) {
+        assert_eq!(estimate.lines.len(), expected.len());
+        for (line, &(start, seconds)) in estimate.lines.iter().zip(expected) {
+            assert_close(line.start_seconds, start);
+            assert_close(line.seconds, seconds);
+        }
+    }
+
+    #[test]
+    fn counts_latin_words_and_cjk_characters() {
+        let measured = measure_line("Hello world 你好 2024 年 café don't 3.14");
+        assert_eq!((measured.words, measured.ideographs), (6, 3));
+        let measured = measure_line("こんにちは 안녕 OK");
+        assert_eq!((measured.words, measured.ideographs), (1, 7));
+        assert_eq!(measure_line("  —— “” ").units(), 0.0);
+    }
+
+    #[test]
+    fn punctuation_runs_pause_for_their_longest_mark() {
+        assert_close(measure_line("Wait...!? Go, now.").pause_seconds, 1.25);
+        assert_close(measure_line("你好，世界。。。").pause_seconds, 0.75);
+        assert_close(measure_line("一、二；三").pause_seconds, 0.5);
+        assert_close(measure_line("“Hi” there").pause_seconds, 0.0);
+    }
+
+    #[test]
+    fn times_lines_with_cues_and_paragraph_pauses() {
+        let estimate = estimate(
+            "one two.\n\n   \n[speed 2]three four[note: smile][pause 1.5]\n五六",
+            60.0,
+        );
+        assert_eq!((estimate.word_count, estimate.ideograph_count), (4, 2));
+        assert_close(estimate.total_units, 6.0);
+        assert_lines(
+            &estimate,
+            &[(0.0, 2.5), (2.5, 0.6), (3.1, 0.6), (3.7, 2.5), (6.2, 1.0)],
+        );
+        assert_close(estimate.lines[1].pause_seconds, PARAGRAPH_PAUSE);
+        assert_close(estimate.total_seconds, 7.2);
+        assert!(!estimate.timed);
+    }
+
+    #[test]
+    fn lines_before_the_first_cue_lead_up_to_it() {
+        let estimate = estimate("intro words\n[at 10-12]cue one", 60.0);
+        assert!(estimate.timed);
+        assert_lines(&estimate, &[(0.0, 10.0), (10.0, 2.0)]);
+        assert_close(estimate.total_seconds, 12.0);
+    }
+
+    #[test]
+    fn uncued_lines_share_the_span_and_hold_the_gap() {
+        let estimate = estimate("[at 0-6]one\ntwo three\n[at 10]four", 60.0);
+        // One and two-three read 1s and 2s, stretched over 6s; the last line
+        // holds the 4s until the next cue, which runs for its reading time.
+        assert_lines(&estimate, &[(0.0, 2.0), (2.0, 8.0), (10.0, 1.0)]);
+        assert_close(estimate.lines[1].pause_seconds, 4.0);
+        assert_close(estimate.total_seconds, 11.0);
+    }
+
+    #[test]
+    fn overlapping_and_out_of_order_cues_start_when_the_previous_ends() {
+        let estimate = estimate("[at 5-8]one\n[at 3-4]two\n[at 9]three", 60.0);
+        assert_lines(&estimate, &[(5.0, 0.0), (5.0, 4.0), (9.0, 1.0)]);
+        assert_close(estimate.total_seconds, 10.0);
+    }
+
+    #[test]
+    fn unread_lines_give_the_span_to_their_first_line() {
+        let estimate = estimate("[at 2-4][pause 1]\n[note: smile]\n[at 6-7]go", 60.0);
+        assert_lines(&estimate, &[(2.0, 2.0), (4.0, 2.0), (6.0, 1.0)]);
+        assert_close(estimate.lines[0].pause_seconds, 0.0);
+        assert_close(estimate.lines[1].pause_seconds, 2.0);
+        assert_close(estimate.total_seconds, 7.0);
+    }
+
+    #[test]
+    fn clamps_the_rate() {
+        for rate in [0.0, -30.0] {
+            let estimate = estimate("one", rate);
+            assert_close(estimate.rate, MIN_RATE);
+            assert_close(estimate.total_seconds, 60.0);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Tray icon with the everyday controls, so the app can run with its window
+//! hidden. The menu is rebuilt when the settings change and when the pointer
+//! enters the icon, which keeps the recent scripts and profiles current.
+
+use std::sync::Arc;
+
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::capture;
+use crate::config::Config;
+use crate::control::{AppControl, Control, Controller};
+use crate::profiles;
+use crate::scripts::{self, ScriptLibrary};
+
+pub const TRAY_ID: &str = "main";
+const RECENT_SCRIPTS: usize = 5;
+const SCRIPT_PREFIX: &str = "script:";
+const PROFILE_PREFIX: &str = "profile:";
+
+/// Shows and focuses the main window, or hides it when it is showing.
+pub fn toggle_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let settings = app.state::<Config>().get();
+    let scripts = app.state::<ScriptLibrary>().list().unwrap_or_default();
+
+    let recent = Submenu::with_id(app, "recent", "最近的稿件", !scripts.is_empty())?;
+    for script in scripts.iter().take(RECENT_SCRIPTS) {
+        let id = format!("{SCRIPT_PREFIX}{}", script.id);
+        recent.append(&MenuItem::with_id(
+            app,
+            id,
+            &script.title,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    let profiles = Submenu::with_id(app, "profiles", "方案", !settings.profiles.is_empty())?;
+    for profile in &settings.profiles {
+        let id = format!("{PROFILE_PREFIX}{}", profile.name);
+        let active = settings.active_profile.as_deref() == Some(profile.name.as_str());
+        profiles.append(&CheckMenuItem::with_id(
+            app,
+            id,
+            &profile.name,
+            true,
+            active,
+            None::<&str>,
+        )?)?;
+    }
+
+    Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, "show", "显示 / 隐藏", true, None::<&str>)?,
+            &MenuItem::with_id(app, "toggle-play", "播放 / 暂停", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &recent,
+            &profiles,
+            &CheckMenuItem::with_id(
+                app,
+                "capture",
+                "防录屏",
+                true,
+                capture::all_enabled(app),
+                None::<&str>,
+            )?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?,
+        ],
+    )
+}
+
+/// Rebuilds the tray menu from the current settings and script library.
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    if let Ok(menu) = build_menu(app) {
+        let _ = tray.set_menu(Some(menu));
+    }
+}
+
+fn open_script(app: &AppHandle, id: &str) {
+    if let Ok(script) = app.state::<ScriptLibrary>().load(id) {
+        scripts::open_in_editor(app, script);
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        "show" => toggle_main_window(app),
+        "toggle-play" => {
+            let _ = app.state::<Arc<AppControl>>().apply(Control::Toggle);
+        }
+        "capture" => {
+            let enabled = !capture::all_enabled(app);
+            let _ = capture::set_enabled(app, &capture::KNOWN_WINDOWS, enabled);
+        }
+        "quit" => {
+            crate::before_exit(app);
+            app.exit(0);
+        }
+        id => {
+            if let Some(id) = id.strip_prefix(SCRIPT_PREFIX) {
+                open_script(app, id);
+            } else if let Some(name) = id.strip_prefix(PROFILE_PREFIX) {
+                let _ = profiles::activate(app, app.state::<Config>().switch_profile(name));
+            }
+        }
+    }
+}
+
+pub fn build(app: &AppHandle) -> tauri::Result<()> {
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Flash Prompter")
+        .menu(&build_menu(app)?)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(|tray, event| match event {
+            TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } => toggle_main_window(tray.app_handle()),
+            TrayIconEvent::Enter { .. } => refresh(tray.app_handle()),
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
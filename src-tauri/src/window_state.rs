@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{LogicalPosition, LogicalSize, Monitor, Runtime, Window};
+
+use crate::config::WindowGeometry;
+use crate::placement::monitor_bounds;
+
+/// Identifies a monitor across launches. The scale factor is part of the key
+/// because a geometry saved at 150% does not fit the same display at 100%.
+pub fn monitor_key(monitor: &Monitor) -> String {
+    let size = monitor.size();
+    format!(
+        "{}:{}x{}@{}",
+        monitor.name().map(String::as_str).unwrap_or("unknown"),
+        size.width,
+        size.height,
+        monitor.scale_factor()
+    )
+}
+
+/// Places a geometry stored relative to its monitor back onto `bounds`,
+/// pulling it inside if it would hang off the edge.
+fn restore_onto(relative: WindowGeometry, bounds: WindowGeometry) -> WindowGeometry {
+    let width = relative.width.min(bounds.width);
+    let height = relative.height.min(bounds.height);
+    WindowGeometry {
+        x: bounds.x + relative.x.clamp(0.0, bounds.width - width),
+        y: bounds.y + relative.y.clamp(0.0, bounds.height - height),
+        width,
+        height,
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StoredState {
+    last_monitor: Option<String>,
+    /// Window geometry per monitor key, relative to that monitor's origin.
+    monitors: BTreeMap<String, WindowGeometry>,
+}
+
+/// Last window geometry per monitor, persisted in `window-state.json`.
+pub struct WindowState {
+    path: PathBuf,
+    state: Mutex<StoredState>,
+}
+
+impl WindowState {
+    pub fn load(path: PathBuf) -> Self {
+        let state = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Key of the monitor the window was last on.
+    pub fn last_monitor(&self) -> Option<String> {
+        self.state.lock().ok()?.last_monitor.clone()
+    }
+
+    /// Moves the window back to where it was last left on `monitor`. Returns
+    /// false when nothing was saved for that monitor.
+    pub fn restore_on<R: Runtime>(&self, window: &Window<R>, monitor: &Monitor) -> bool {
+        let key = monitor_key(monitor);
+        let Some(relative) = self
+            .state
+            .lock()
+            .ok()
+            .and_then(|state| state.monitors.get(&key).copied())
+        else {
+            return false;
+        };
+        let geometry = restore_onto(relative, monitor_bounds(monitor));
+        let _ = window.set_size(LogicalSize::new(geometry.width, geometry.height));
+        let _ = window.set_position(LogicalPosition::new(geometry.x, geometry.y));
+        true
+    }
+
+    /// Remembers the window's current geometry for the monitor it is on.
+    pub fn record<R: Runtime>(&self, window: &Window<R>) {
+        if window.is_minimized().unwrap_or(false) || window.is_maximized().unwrap_or(false) {
+            return;
+        }
+        let Some(monitor) = window.current_monitor().ok().flatten() else {
+            return;
+        };
+        let (Ok(position), Ok(size)) = (window.outer_position(), window.outer_size()) else {
+            return;
+        };
+        let scale_factor = monitor.scale_factor();
+        let position = position.to_logical::<f64>(scale_factor);
+        let size = size.to_logical::<f64>(scale_factor);
+        let bounds = monitor_bounds(&monitor);
+        let key = monitor_key(&monitor);
+        if let Ok(mut state) = self.state.lock() {
+            state.monitors.insert(
+                key.clone(),
+                WindowGeometry {
+                    x: position.x - bounds.x,
+                    y: position.y - bounds.y,
+                    width: size.width,
+                    height: size.height,
+                },
+            );
+            state.last_monitor = Some(key);
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let json = {
+            let state = self.state.lock().map_err(|e| e.to_string())?;
+            serde_json::to_string_pretty(&*state).map_err(|e| e.to_string())?
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(&self.path, json).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(x: f64, y: f64, width: f64, height: f64) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn restores_relative_to_monitor_origin() {
+        let bounds = geometry(-1920.0, -200.0, 1920.0, 1080.0);
+        let restored = restore_onto(geometry(100.0, 40.0, 420.0, 300.0), bounds);
+        assert_eq!(restored, geometry(-1820.0, -160.0, 420.0, 300.0));
+    }
+
+    #[test]
+    fn keeps_restored_window_on_screen() {
+        let bounds = geometry(0.0, 0.0, 1280.0, 720.0);
+        let restored = restore_onto(geometry(1200.0, -50.0, 420.0, 900.0), bounds);
+        assert_eq!(restored, geometry(860.0, 0.0, 420.0, 720.0));
+    }
+}
@@ -1,9 +1,20 @@
-import { useEffect, useMemo, useRef, useState } from "react";
+import { useEffect, useRef, useState, type KeyboardEvent as ReactKeyboardEvent } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
-import { disable, enable, isEnabled } from "@tauri-apps/plugin-autostart";
 import { relaunch } from "@tauri-apps/plugin-process";
 import { check } from "@tauri-apps/plugin-updater";
-import { FiArrowLeft, FiPause, FiPlay, FiRefreshCw, FiSettings, FiSquare } from "react-icons/fi";
+import {
+  FiArrowLeft,
+  FiFileText,
+  FiFolder,
+  FiMonitor,
+  FiPause,
+  FiPlay,
+  FiRefreshCw,
+  FiSettings,
+  FiSquare
+} from "react-icons/fi";
 
 const DEFAULT_TEXT = `欢迎使用 Flash Prompter
 
@@ -15,46 +26,312 @@ const DEFAULT_TEXT = `欢迎使用 Flash Prompter
 const WORDS_PER_MINUTE = 60;
 const LINE_HEIGHT = 48;
 const FONT_SIZE = 34;
-const SETTINGS_KEY = "flash-prompter-settings";
+const LEGACY_SETTINGS_KEY = "flash-prompter-settings";
 
 type Mode = "input" | "prompter" | "settings";
 
+const IS_OUTPUT_WINDOW = getCurrentWebviewWindow().label === "talent";
+
+type PlaybackSnapshot = {
+  state: "idle" | "playing" | "paused" | "finished";
+  progress: number;
+  elapsedSeconds: number;
+  durationSeconds: number;
+  line: number;
+  lineText: string;
+  lineProgress: number;
+  timed: boolean;
+};
+
+const formatTime = (seconds: number) => {
+  const total = Math.max(0, Math.round(seconds));
+  return `${Math.floor(total / 60)}:${String(total % 60).padStart(2, "0")}`;
+};
+
+const withOpacity = (hex: string, opacity: number) => {
+  const value = parseInt(hex.slice(1), 16);
+  return `rgba(${(value >> 16) & 255}, ${(value >> 8) & 255}, ${value & 255}, ${opacity})`;
+};
+
+type Inline =
+  | { type: "text"; text: string }
+  | { type: "emphasis"; text: string }
+  | { type: "pause"; seconds: number }
+  | { type: "speed"; factor: number }
+  | { type: "note"; text: string }
+  | { type: "section"; title: string }
+  | { type: "time"; start: number; end: number | null };
+
+type ParseError = {
+  line: number;
+  column: number;
+  length: number;
+  message: string;
+};
+
+type ParsedScript = {
+  document: { lines: { inlines: Inline[] }[] };
+  errors: ParseError[];
+};
+
+type RemoteStatus = {
+  config: { enabled: boolean; bind: "localhost" | "lan"; port: number };
+  address: string | null;
+};
+
+type RemotePairing = {
+  url: string;
+  code: string;
+  qrSvg: string;
+};
+
+type HotkeyAction =
+  | "togglePlay"
+  | "faster"
+  | "slower"
+  | "lineBack"
+  | "hideWindow"
+  | "nextProfile"
+  | "toggleMirror"
+  | "toggleOverlay";
+
+type HotkeyBinding = {
+  action: HotkeyAction;
+  accelerator: string;
+  registered: boolean;
+  error: string | null;
+};
+
+const HOTKEY_LABELS: Record<HotkeyAction, string> = {
+  togglePlay: "播放/暂停",
+  faster: "加速",
+  slower: "减速",
+  lineBack: "回退一行",
+  hideWindow: "隐藏窗口",
+  nextProfile: "切换方案",
+  toggleMirror: "镜像输出",
+  toggleOverlay: "悬浮穿透"
+};
+
+const KEY_NAMES: Record<string, string> = {
+  " ": "Space",
+  ArrowUp: "Up",
+  ArrowDown: "Down",
+  ArrowLeft: "Left",
+  ArrowRight: "Right"
+};
+
+const acceleratorFromEvent = (event: ReactKeyboardEvent) => {
+  if (["Control", "Shift", "Alt", "Meta"].includes(event.key)) return null;
+  const parts = [];
+  if (event.ctrlKey || event.metaKey) parts.push("CommandOrControl");
+  if (event.altKey) parts.push("Alt");
+  if (event.shiftKey) parts.push("Shift");
+  const key = KEY_NAMES[event.key] ?? (event.key.length === 1 ? event.key.toUpperCase() : event.key);
+  parts.push(key);
+  return parts.join("+");
+};
+
+type AutostartLaunch = {
+  startHidden: boolean;
+  restoreLastScript: boolean;
+  skipUpdateCheck: boolean;
+};
+
+type ScriptSummary = { id: string; title: string; tags: string[]; createdAt: number; modifiedAt: number };
+
+type Script = ScriptSummary & { body: string };
+
+type OpenedFile = { path: string; title: string; text: string; encoding: string };
+
+type LaunchInfo = AutostartLaunch & { autostart: boolean; file: OpenedFile | null };
+
+const AUTOSTART_LAUNCH_LABELS: Record<keyof AutostartLaunch, string> = {
+  startHidden: "隐藏到托盘",
+  restoreLastScript: "打开最近的稿件",
+  skipUpdateCheck: "跳过更新检查"
+};
+
+type LinkAction = "open" | "load" | "play" | "pause" | "toggle" | "stop";
+
+const LINK_ACTION_LABELS: Record<LinkAction, string> = {
+  open: "下载并打开网址",
+  load: "打开稿件库",
+  play: "播放",
+  pause: "暂停",
+  toggle: "播放/暂停",
+  stop: "停止"
+};
+
 type Settings = {
   wordsPerMinute: number;
   fontSize: number;
   lineHeight: number;
+  textColor: string;
+  backgroundColor: string;
   autoStart: boolean;
+  autostartLaunch: AutostartLaunch;
+  placement: Placement;
+  targetMonitor: string | null;
+  outputMonitor: string | null;
+  mirror: { horizontal: boolean; vertical: boolean };
+  overlay: { enabled: boolean; opacity: number };
+  captureExclusion: Record<string, boolean>;
+  linkAllowlist: LinkAction[];
+  activeProfile: string | null;
+  profiles: Profile[];
+};
+
+type Anchor =
+  | "topLeft"
+  | "topCenter"
+  | "topRight"
+  | "centerLeft"
+  | "center"
+  | "centerRight"
+  | "bottomLeft"
+  | "bottomCenter"
+  | "bottomRight";
+
+type Placement =
+  | { mode: "centered" | "underWebcam" | "leftGutter" | "rightGutter" }
+  | { mode: "custom"; anchor: Anchor; offsetX: number; offsetY: number };
+
+const PLACEMENT_LABELS: Record<Placement["mode"], string> = {
+  centered: "居中偏上",
+  underWebcam: "摄像头正下方",
+  leftGutter: "左侧贴边",
+  rightGutter: "右侧贴边",
+  custom: "自定义"
+};
+
+const ANCHOR_LABELS: Record<Anchor, string> = {
+  topLeft: "左上",
+  topCenter: "上中",
+  topRight: "右上",
+  centerLeft: "左中",
+  center: "正中",
+  centerRight: "右中",
+  bottomLeft: "左下",
+  bottomCenter: "下中",
+  bottomRight: "右下"
+};
+
+type MonitorInfo = {
+  key: string;
+  name: string | null;
+  width: number;
+  height: number;
+  scaleFactor: number;
+  primary: boolean;
+};
+
+type CaptureStatus =
+  | { status: "applied" }
+  | { status: "disabled" }
+  | { status: "failed"; code: number; message: string }
+  | { status: "unsupported" };
+
+type WindowCapture = {
+  label: string;
+  enabled: boolean;
+  status: CaptureStatus;
+};
+
+type Profile = {
+  name: string;
+  wordsPerMinute: number;
+  fontSize: number;
+  lineHeight: number;
+  textColor: string;
+  backgroundColor: string;
 };
 
 const DEFAULT_SETTINGS: Settings = {
   wordsPerMinute: WORDS_PER_MINUTE,
   fontSize: FONT_SIZE,
   lineHeight: LINE_HEIGHT,
-  autoStart: false
+  textColor: "#f5f5f5",
+  backgroundColor: "#0f0f0f",
+  autoStart: false,
+  autostartLaunch: { startHidden: true, restoreLastScript: false, skipUpdateCheck: true },
+  placement: { mode: "centered" },
+  targetMonitor: null,
+  outputMonitor: null,
+  mirror: { horizontal: false, vertical: false },
+  overlay: { enabled: false, opacity: 0.6 },
+  captureExclusion: {},
+  linkAllowlist: ["load", "play", "pause", "toggle", "stop"],
+  activeProfile: null,
+  profiles: []
 };
 
 export default function App() {
   const [mode, setMode] = useState<Mode>("input");
   const [isPlaying, setIsPlaying] = useState(false);
   const [progress, setProgress] = useState(0);
+  const [snapshot, setSnapshot] = useState<PlaybackSnapshot | null>(null);
   const [content, setContent] = useState(DEFAULT_TEXT);
+  const [scripts, setScripts] = useState<ScriptSummary[]>([]);
+  const [scriptId, setScriptId] = useState<string | null>(null);
+  const [scriptTitle, setScriptTitle] = useState("");
+  const [scriptError, setScriptError] = useState<string | null>(null);
+  const [parsed, setParsed] = useState<ParsedScript | null>(null);
   const [settings, setSettings] = useState<Settings>(DEFAULT_SETTINGS);
+  const [remote, setRemote] = useState<RemoteStatus | null>(null);
+  const [pairing, setPairing] = useState<RemotePairing | null>(null);
+  const [hotkeys, setHotkeys] = useState<HotkeyBinding[]>([]);
+  const [hotkeyError, setHotkeyError] = useState<string | null>(null);
+  const [monitors, setMonitors] = useState<MonitorInfo[]>([]);
+  const [profileName, setProfileName] = useState("");
+  const [profileError, setProfileError] = useState<string | null>(null);
+  const [captures, setCaptures] = useState<WindowCapture[]>([]);
+  const [captureWarning, setCaptureWarning] = useState<WindowCapture | null>(null);
+  const [linkNotice, setLinkNotice] = useState<string | null>(null);
   const scrollRef = useRef<HTMLDivElement | null>(null);
-  const startTimeRef = useRef<number | null>(null);
-  const startProgressRef = useRef(0);
   const windowRef = useRef<ReturnType<typeof getCurrentWebviewWindow> | null>(null);
   const settingsReturnModeRef = useRef<Mode>("input");
 
-  const words = useMemo(() => content.trim().split(/\s+/).filter(Boolean), [content]);
-  const totalWords = words.length || 1;
-  const wordsPerSecond = settings.wordsPerMinute / 60;
-  const totalSeconds = totalWords / wordsPerSecond;
-
   useEffect(() => {
     windowRef.current = getCurrentWebviewWindow();
   }, []);
 
   useEffect(() => {
+    const unlisten = listen<PlaybackSnapshot>("playback://position", (event) => {
+      setProgress(event.payload.progress);
+      setIsPlaying(event.payload.state === "playing");
+      setSnapshot(event.payload);
+    });
+    return () => {
+      unlisten.then((off) => off());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (!IS_OUTPUT_WINDOW) return;
+    const unlisten = listen<ParsedScript["document"]>("playback://document", (event) => {
+      setParsed({ document: event.payload, errors: [] });
+    });
+    invoke<ParsedScript["document"]>("playback_document")
+      .then((document) => setParsed({ document, errors: [] }))
+      .catch(() => setParsed(null));
+    return () => {
+      unlisten.then((off) => off());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (IS_OUTPUT_WINDOW) return;
+    invoke("playback_load", { text: content }).catch(() => {
+      return;
+    });
+    invoke<ParsedScript>("parse_script", { text: content })
+      .then(setParsed)
+      .catch(() => setParsed(null));
+  }, [content]);
+
+  useEffect(() => {
+    if (IS_OUTPUT_WINDOW) return;
     const runUpdate = async () => {
       try {
         const update = await check();
@@ -66,84 +343,143 @@ export default function App() {
         return;
       }
     };
-    runUpdate();
+    const restoreLastScript = async () => {
+      try {
+        const [latest] = await invoke<ScriptSummary[]>("list_scripts");
+        if (latest) {
+          showScript(await invoke<Script>("load_script", { id: latest.id }));
+        }
+      } catch {
+        return;
+      }
+    };
+    const start = async () => {
+      const launch = await invoke<LaunchInfo>("launch_info").catch(() => null);
+      if (launch?.file) showFile(launch.file);
+      if (launch?.restoreLastScript) restoreLastScript();
+      if (!launch?.skipUpdateCheck) runUpdate();
+    };
+    start();
+  }, []);
+
+  useEffect(() => {
+    if (IS_OUTPUT_WINDOW) return;
+    const unlisten = listen<Script>("scripts://open", (event) => {
+      showScript(event.payload);
+      setMode("input");
+    });
+    const unlistenFile = listen<OpenedFile>("launch://open-file", (event) => {
+      showFile(event.payload);
+      setMode("input");
+    });
+    const unlistenLink = listen<{ link: string; reason: string }>("deep-link://rejected", (event) => {
+      setLinkNotice(`链接未执行：${event.payload.link}（${event.payload.reason}）`);
+    });
+    return () => {
+      unlisten.then((off) => off());
+      unlistenFile.then((off) => off());
+      unlistenLink.then((off) => off());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (IS_OUTPUT_WINDOW) return;
+    const unlisten = listen<WindowCapture>("capture://warning", (event) => {
+      setCaptureWarning(event.payload);
+    });
+    invoke<WindowCapture[]>("capture_status")
+      .then((list) => setCaptureWarning(list.find((item) => item.status.status === "failed") ?? null))
+      .catch(() => {
+        return;
+      });
+    return () => {
+      unlisten.then((off) => off());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (IS_OUTPUT_WINDOW) return;
+    refreshScripts();
   }, []);
 
   useEffect(() => {
-    const clamp = (value: number, min: number, max: number) =>
-      Math.min(max, Math.max(min, value));
-
-    const normalizeSettings = (value: Partial<Settings>): Settings => ({
-      wordsPerMinute: clamp(Number(value.wordsPerMinute ?? WORDS_PER_MINUTE), 20, 240),
-      fontSize: clamp(Number(value.fontSize ?? FONT_SIZE), 20, 64),
-      lineHeight: clamp(Number(value.lineHeight ?? LINE_HEIGHT), 28, 96),
-      autoStart: Boolean(value.autoStart)
+    const unlisten = listen<Settings>("settings://changed", (event) => {
+      setSettings(event.payload);
     });
 
     const load = async () => {
-      let stored: Partial<Settings> = {};
       try {
-        const raw = localStorage.getItem(SETTINGS_KEY);
-        if (raw) stored = JSON.parse(raw);
+        const legacy = IS_OUTPUT_WINDOW ? null : localStorage.getItem(LEGACY_SETTINGS_KEY);
+        if (legacy) {
+          localStorage.removeItem(LEGACY_SETTINGS_KEY);
+          setSettings(await invoke<Settings>("import_legacy_settings", { legacy: JSON.parse(legacy) }));
+          return;
+        }
       } catch {
-        stored = {};
+        localStorage.removeItem(LEGACY_SETTINGS_KEY);
       }
-
-      let autoStart = false;
       try {
-        autoStart = await isEnabled();
+        setSettings(await invoke<Settings>("get_settings"));
       } catch {
-        autoStart = false;
+        return;
       }
-
-      setSettings(normalizeSettings({ ...stored, autoStart }));
     };
 
     load();
+    return () => {
+      unlisten.then((off) => off());
+    };
   }, []);
 
   useEffect(() => {
-    try {
-      localStorage.setItem(SETTINGS_KEY, JSON.stringify(settings));
-    } catch {
+    document.body.style.background = settings.overlay.enabled && !IS_OUTPUT_WINDOW ? "transparent" : "";
+  }, [settings.overlay.enabled]);
+
+  useEffect(() => {
+    const container = scrollRef.current;
+    if (!container) return;
+    const maxScroll = container.scrollHeight - container.clientHeight;
+    // Timed scripts follow the current cue line rather than overall progress.
+    const first = container.children[0] as HTMLElement | undefined;
+    const line = snapshot?.timed ? (container.children[snapshot.line] as HTMLElement | undefined) : undefined;
+    if (first && line) {
+      const target = line.offsetTop - first.offsetTop + line.offsetHeight * snapshot!.lineProgress;
+      container.scrollTop = Math.min(maxScroll, Math.max(0, target - container.clientHeight / 3));
       return;
     }
-  }, [settings]);
+    container.scrollTop = maxScroll * progress;
+  }, [progress, snapshot, content, parsed]);
 
   useEffect(() => {
-    let raf = 0;
+    if (mode !== "settings") return;
+    invoke<RemoteStatus>("remote_status")
+      .then(setRemote)
+      .catch(() => setRemote(null));
+  }, [mode]);
 
-    const tick = (time: number) => {
-      if (startTimeRef.current === null) {
-        startTimeRef.current = time;
-      }
-      const elapsed = (time - startTimeRef.current) / 1000;
-      const nextProgress = startProgressRef.current + elapsed / totalSeconds;
-      if (nextProgress >= 1) {
-        setProgress(1);
-        startProgressRef.current = 1;
-        setIsPlaying(false);
-        return;
-      }
-      setProgress(nextProgress);
-      raf = requestAnimationFrame(tick);
-    };
-
-    if (isPlaying) {
-      startTimeRef.current = null;
-      raf = requestAnimationFrame(tick);
-    }
-
-    return () => {
-      if (raf) cancelAnimationFrame(raf);
-    };
-  }, [isPlaying, totalSeconds]);
+  useEffect(() => {
+    if (mode !== "settings") return;
+    setHotkeyError(null);
+    invoke<HotkeyBinding[]>("get_hotkeys")
+      .then(setHotkeys)
+      .catch(() => setHotkeys([]));
+    invoke<MonitorInfo[]>("list_monitors")
+      .then(setMonitors)
+      .catch(() => setMonitors([]));
+    invoke<WindowCapture[]>("capture_status")
+      .then(setCaptures)
+      .catch(() => setCaptures([]));
+  }, [mode]);
 
   useEffect(() => {
-    if (!scrollRef.current) return;
-    const maxScroll = scrollRef.current.scrollHeight - scrollRef.current.clientHeight;
-    scrollRef.current.scrollTop = maxScroll * progress;
-  }, [progress, content]);
+    if (mode !== "settings" || !remote?.address || remote.config.bind !== "lan") {
+      setPairing(null);
+      return;
+    }
+    invoke<RemotePairing>("remote_pairing")
+      .then(setPairing)
+      .catch(() => setPairing(null));
+  }, [mode, remote]);
 
   const enterPrompter = async () => {
     setMode("prompter");
@@ -160,7 +496,9 @@ export default function App() {
   const openSettings = () => {
     if (mode === "settings") return;
     settingsReturnModeRef.current = mode;
-    setIsPlaying(false);
+    invoke("playback_pause").catch(() => {
+      return;
+    });
     setMode("settings");
   };
 
@@ -170,60 +508,189 @@ export default function App() {
 
   const updateSettings = (next: Partial<Settings>) => {
     setSettings((prev) => ({ ...prev, ...next }));
+    invoke("update_settings", { patch: next }).catch(() => {
+      return;
+    });
+  };
+
+  const toggleAutoStart = () => {
+    updateSettings({ autoStart: !settings.autoStart });
   };
 
-  const toggleAutoStart = async () => {
-    const next = !settings.autoStart;
-    setSettings((prev) => ({ ...prev, autoStart: next }));
+  const phoneRemoteEnabled = Boolean(remote?.config.enabled && remote.config.bind === "lan");
+
+  const togglePhoneRemote = async () => {
+    if (!remote) return;
     try {
-      if (next) {
-        await enable();
-      } else {
-        await disable();
-      }
+      const next = await invoke<RemoteStatus>("update_remote", {
+        enabled: !phoneRemoteEnabled,
+        bind: "lan",
+        port: remote.config.port
+      });
+      setRemote(next);
     } catch {
-      setSettings((prev) => ({ ...prev, autoStart: !next }));
+      return;
+    }
+  };
+
+  const applyPlacement = async (placement: Placement) => {
+    setSettings((prev) => ({ ...prev, placement }));
+    try {
+      setSettings(await invoke<Settings>("apply_placement", { placement }));
+    } catch {
+      return;
+    }
+  };
+
+  const pinMonitor = async (key: string | null) => {
+    try {
+      setSettings(await invoke<Settings>("set_target_monitor", { key }));
+    } catch {
+      return;
+    }
+  };
+
+  const chooseOutputMonitor = async (key: string | null) => {
+    try {
+      setSettings(await invoke<Settings>("set_output_monitor", { key }));
+    } catch {
+      return;
+    }
+  };
+
+  const setCaptureExclusion = async (label: string, enabled: boolean) => {
+    try {
+      const list = await invoke<WindowCapture[]>("set_capture_exclusion", { label, enabled });
+      setCaptures(list);
+      setCaptureWarning(list.find((item) => item.status.status === "failed") ?? null);
+    } catch {
+      return;
+    }
+  };
+
+  const captureLabel = (capture: WindowCapture) => {
+    const window = capture.label === "talent" ? "输出窗口" : "主窗口";
+    switch (capture.status.status) {
+      case "applied":
+        return `${window}：已隐藏`;
+      case "disabled":
+        return `${window}：未隐藏`;
+      case "failed":
+        return `${window}：失败（${capture.status.code}）${capture.status.message}`;
+      case "unsupported":
+        return `${window}：当前系统不支持`;
+    }
+  };
+
+  const renderCaptureWarning = () =>
+    captureWarning && (
+      <div
+        style={{
+          fontSize: 13,
+          color: "#fbbf24",
+          border: "1px solid #78350f",
+          borderRadius: 8,
+          padding: "6px 10px"
+        }}
+      >
+        防录屏失败，提词内容可能出现在录屏或屏幕共享中：{captureLabel(captureWarning)}
+      </div>
+    );
+
+  const monitorLabel = (monitor: MonitorInfo, index: number) =>
+    `${monitor.name || `显示器 ${index + 1}`} · ${monitor.width}×${monitor.height}` +
+    (monitor.scaleFactor !== 1 ? ` @${monitor.scaleFactor}x` : "") +
+    (monitor.primary ? "（主）" : "");
+
+  const customPlacement =
+    settings.placement.mode === "custom"
+      ? settings.placement
+      : { mode: "custom" as const, anchor: "topCenter" as Anchor, offsetX: 0, offsetY: 0 };
+
+  const runProfileCommand = async (command: string, args?: Record<string, unknown>) => {
+    try {
+      setSettings(await invoke<Settings>(command, args));
+      setProfileError(null);
+    } catch (error) {
+      setProfileError(String(error));
+    }
+  };
+
+  const saveProfile = async () => {
+    const name = profileName.trim() || settings.activeProfile;
+    if (!name) return;
+    await runProfileCommand("save_profile", { name });
+    setProfileName("");
+  };
+
+  const exportProfiles = async () => {
+    try {
+      await invoke<boolean>("export_profiles");
+      setProfileError(null);
+    } catch (error) {
+      setProfileError(String(error));
+    }
+  };
+
+  const bindHotkey = async (action: HotkeyAction, accelerator: string) => {
+    try {
+      setHotkeys(await invoke<HotkeyBinding[]>("set_hotkey", { action, accelerator }));
+      setHotkeyError(null);
+    } catch (error) {
+      setHotkeyError(String(error));
+    }
+  };
+
+  const resetHotkeys = async () => {
+    try {
+      setHotkeys(await invoke<HotkeyBinding[]>("reset_hotkeys"));
+      setHotkeyError(null);
+    } catch (error) {
+      setHotkeyError(String(error));
     }
   };
 
   const restoreDefaults = async () => {
-    setSettings(DEFAULT_SETTINGS);
     try {
-      await disable();
+      setSettings(await invoke<Settings>("reset_settings"));
     } catch {
       return;
     }
   };
 
-  const handleStart = () => {
-    if (mode === "input") {
-      setProgress(0);
-      startProgressRef.current = 0;
-      enterPrompter();
-    } else {
-      startProgressRef.current = progress;
+  const handleStart = async () => {
+    try {
+      if (mode === "input") {
+        await invoke("playback_seek", { progress: 0 });
+        enterPrompter();
+      }
+      await invoke("playback_play");
+    } catch {
+      return;
     }
-    startTimeRef.current = null;
-    setIsPlaying(true);
   };
 
-  const handlePause = () => {
+  const handlePause = async () => {
     if (!isPlaying) return;
-    setIsPlaying(false);
-    startProgressRef.current = progress;
+    try {
+      await invoke("playback_pause");
+    } catch {
+      return;
+    }
   };
 
-  const handleStop = () => {
-    setIsPlaying(false);
-    setProgress(0);
-    startProgressRef.current = 0;
-    startTimeRef.current = null;
+  const handleStop = async () => {
+    try {
+      await invoke("playback_stop");
+    } catch {
+      return;
+    }
     exitPrompter();
   };
 
   useEffect(() => {
     const onKey = (event: KeyboardEvent) => {
-      if (mode === "settings") return;
+      if (IS_OUTPUT_WINDOW || mode === "settings") return;
       if (event.code === "Space") {
         event.preventDefault();
         if (mode === "input") {
@@ -237,7 +704,7 @@ export default function App() {
     };
     window.addEventListener("keydown", onKey);
     return () => window.removeEventListener("keydown", onKey);
-  }, [isPlaying, mode, progress]);
+  }, [isPlaying, mode]);
 
   const baseButtonStyle = {
     width: 44,
@@ -258,6 +725,245 @@ export default function App() {
     cursor: enabled ? "pointer" : "not-allowed"
   });
 
+  const renderInline = (inline: Inline, index: number) => {
+    switch (inline.type) {
+      case "text":
+        return <span key={index}>{inline.text}</span>;
+      case "emphasis":
+        return (
+          <strong key={index} style={{ color: "#facc15" }}>
+            {inline.text}
+          </strong>
+        );
+      case "pause":
+        return (
+          <span key={index} style={{ color: "#6b7280", fontSize: "0.6em" }}>
+            {` ⏸ ${inline.seconds}s `}
+          </span>
+        );
+      case "note":
+        return (
+          <span key={index} style={{ color: "#60a5fa", fontSize: "0.6em" }}>
+            {` [${inline.text}] `}
+          </span>
+        );
+      case "time":
+        return (
+          <span key={index} style={{ color: "#6b7280", fontSize: "0.5em" }}>
+            {`⏱ ${formatTime(inline.start)} `}
+          </span>
+        );
+      case "section":
+        return (
+          <span key={index} style={{ color: "#a78bfa", fontSize: "0.6em", letterSpacing: 1 }}>
+            {`§ ${inline.title}`}
+          </span>
+        );
+      default:
+        return null;
+    }
+  };
+
+  const renderScript = () => {
+    if (!parsed) return content;
+    return parsed.document.lines.map((line, index) => (
+      <div key={index}>
+        {line.inlines.length > 0 ? line.inlines.map(renderInline) : "\u00a0"}
+      </div>
+    ));
+  };
+
+  const renderTimeline = () => (
+    <div style={{ display: "flex", flexDirection: "column", gap: 4 }}>
+      <input
+        type="range"
+        min={0}
+        max={1000}
+        value={Math.round(progress * 1000)}
+        onChange={(event) =>
+          invoke("playback_seek", { progress: Number(event.target.value) / 1000 }).catch(() => {
+            return;
+          })
+        }
+        style={{ width: "100%" }}
+      />
+      <div style={{ display: "flex", justifyContent: "space-between", gap: 12, fontSize: 12, color: "#8a8a8a" }}>
+        <span style={{ overflow: "hidden", textOverflow: "ellipsis", whiteSpace: "nowrap" }}>
+          {snapshot?.lineText ?? ""}
+        </span>
+        <span style={{ flexShrink: 0 }}>
+          {formatTime(snapshot?.elapsedSeconds ?? 0)} / {formatTime(snapshot?.durationSeconds ?? 0)}
+        </span>
+      </div>
+    </div>
+  );
+
+  const showScript = (script: Script) => {
+    setContent(script.body);
+    setScriptId(script.id);
+    setScriptTitle(script.title);
+    setScriptError(null);
+  };
+
+  // Files are not in the library until saved, which adds them as new scripts.
+  const showFile = (file: OpenedFile) => {
+    setContent(file.text);
+    setScriptId(null);
+    setScriptTitle(file.title);
+    setScriptError(null);
+  };
+
+  const refreshScripts = async () => {
+    try {
+      setScripts(await invoke<ScriptSummary[]>("list_scripts"));
+    } catch {
+      setScripts([]);
+    }
+  };
+
+  const runScriptCommand = async (command: string, args: Record<string, unknown>) => {
+    try {
+      const script = await invoke<Script | null>(command, args);
+      if (script) {
+        setScriptId(script.id);
+        setScriptTitle(script.title);
+      }
+      setScriptError(null);
+    } catch (error) {
+      setScriptError(String(error));
+    }
+    refreshScripts();
+  };
+
+  const openLibraryScript = async (id: string) => {
+    try {
+      showScript(await invoke<Script>("load_script", { id }));
+    } catch (error) {
+      setScriptError(String(error));
+    }
+  };
+
+  const saveScript = () => {
+    const tags = scripts.find((script) => script.id === scriptId)?.tags ?? [];
+    runScriptCommand("save_script", { script: { id: scriptId, title: scriptTitle, body: content, tags } });
+  };
+
+  const renameScript = () => {
+    if (scriptId) runScriptCommand("rename_script", { id: scriptId, title: scriptTitle });
+  };
+
+  const duplicateScript = () => {
+    if (scriptId) runScriptCommand("duplicate_script", { id: scriptId });
+  };
+
+  const deleteScript = async () => {
+    if (!scriptId) return;
+    await runScriptCommand("delete_script", { id: scriptId });
+    setScriptId(null);
+  };
+
+  const newScript = () => {
+    setContent("");
+    setScriptId(null);
+    setScriptTitle("");
+    setScriptError(null);
+  };
+
+  const libraryButtonStyle = (enabled: boolean) =>
+    ({
+      fontSize: 12,
+      background: "#111",
+      border: "1px solid #2a2a2a",
+      borderRadius: 8,
+      color: "#c7c7c7",
+      cursor: enabled ? "pointer" : "not-allowed",
+      opacity: enabled ? 1 : 0.45,
+      padding: "6px 8px"
+    }) as const;
+
+  const renderLibrary = () => (
+    <div style={{ display: "flex", flexDirection: "column", gap: 6 }}>
+      <div style={{ display: "flex", gap: 8 }}>
+        <select
+          aria-label="稿件库"
+          value={scriptId ?? ""}
+          onChange={(event) => {
+            if (event.target.value) openLibraryScript(event.target.value);
+          }}
+          style={{
+            maxWidth: 200,
+            background: "#111",
+            border: "1px solid #2a2a2a",
+            borderRadius: 8,
+            color: "#f5f5f5",
+            padding: "4px 8px"
+          }}
+        >
+          <option value="">{scripts.length > 0 ? "稿件库" : "稿件库为空"}</option>
+          {scripts.map((script) => (
+            <option key={script.id} value={script.id}>
+              {script.title}
+            </option>
+          ))}
+        </select>
+        <input
+          value={scriptTitle}
+          placeholder="稿件标题"
+          onChange={(event) => setScriptTitle(event.target.value)}
+          style={{
+            flex: 1,
+            background: "#111",
+            border: "1px solid #2a2a2a",
+            borderRadius: 8,
+            color: "#f5f5f5",
+            fontSize: 12,
+            padding: "6px 8px"
+          }}
+        />
+        {[
+          { label: "保存", onClick: saveScript, enabled: true },
+          { label: "重命名", onClick: renameScript, enabled: scriptId !== null },
+          { label: "复制", onClick: duplicateScript, enabled: scriptId !== null },
+          { label: "删除", onClick: deleteScript, enabled: scriptId !== null },
+          { label: "新建", onClick: newScript, enabled: true }
+        ].map((action) => (
+          <button
+            key={action.label}
+            onClick={action.onClick}
+            disabled={!action.enabled}
+            style={libraryButtonStyle(action.enabled)}
+          >
+            {action.label}
+          </button>
+        ))}
+      </div>
+      {scriptError && <div style={{ fontSize: 12, color: "#f87171" }}>{scriptError}</div>}
+    </div>
+  );
+
+  const openTalentWindow = () => {
+    invoke("open_output_window").catch(() => {
+      return;
+    });
+  };
+
+  const openScriptFile = async () => {
+    try {
+      const file = await invoke<OpenedFile | null>("open_script_file");
+      if (file) showFile(file);
+    } catch {
+      return;
+    }
+  };
+
+  const importMarkdown = async () => {
+    try {
+      setContent(await invoke<string>("import_markdown", { text: content }));
+    } catch {
+      return;
+    }
+  };
+
   const renderControls = (currentMode: Mode) => {
     const settingsButton = (
       <button
@@ -269,6 +975,12 @@ export default function App() {
       </button>
     );
 
+    const talentButton = (
+      <button aria-label="打开输出窗口" onClick={openTalentWindow} style={buttonStyle(true)}>
+        <FiMonitor size={18} />
+      </button>
+    );
+
     if (currentMode === "input") {
       return (
         <div style={{ display: "flex", justifyContent: "center", gap: 12 }}>
@@ -279,6 +991,13 @@ export default function App() {
           >
             <FiPlay size={20} />
           </button>
+          <button aria-label="打开文件" onClick={openScriptFile} style={buttonStyle(true)}>
+            <FiFolder size={18} />
+          </button>
+          <button aria-label="转换 Markdown" onClick={importMarkdown} style={buttonStyle(true)}>
+            <FiFileText size={18} />
+          </button>
+          {talentButton}
           {settingsButton}
         </div>
       );
@@ -311,6 +1030,7 @@ export default function App() {
           >
             <FiSquare size={18} />
           </button>
+          {talentButton}
           {settingsButton}
         </div>
       );
@@ -319,6 +1039,65 @@ export default function App() {
     return null;
   };
 
+  if (IS_OUTPUT_WINDOW) {
+    const flip = `scale(${settings.mirror.horizontal ? -1 : 1}, ${settings.mirror.vertical ? -1 : 1})`;
+    return (
+      <div
+        style={{
+          height: "100%",
+          background: settings.backgroundColor,
+          color: settings.textColor,
+          overflow: "hidden",
+          transform: flip
+        }}
+      >
+        <div
+          ref={scrollRef}
+          style={{
+            height: "100%",
+            overflow: "hidden",
+            fontSize: settings.fontSize,
+            lineHeight: `${settings.lineHeight}px`,
+            padding: "8px 24px",
+            boxSizing: "border-box",
+            whiteSpace: "pre-wrap"
+          }}
+        >
+          {renderScript()}
+        </div>
+      </div>
+    );
+  }
+
+  if (settings.overlay.enabled) {
+    return (
+      <div
+        style={{
+          height: "100%",
+          background: withOpacity(settings.backgroundColor, settings.overlay.opacity),
+          color: settings.textColor,
+          borderRadius: 16,
+          overflow: "hidden"
+        }}
+      >
+        <div
+          ref={scrollRef}
+          style={{
+            height: "100%",
+            overflow: "hidden",
+            fontSize: settings.fontSize,
+            lineHeight: `${settings.lineHeight}px`,
+            padding: "8px 16px",
+            boxSizing: "border-box",
+            whiteSpace: "pre-wrap"
+          }}
+        >
+          {renderScript()}
+        </div>
+      </div>
+    );
+  }
+
   if (mode === "settings") {
     return (
       <div
@@ -381,7 +1160,8 @@ export default function App() {
             padding: 16,
             display: "flex",
             flexDirection: "column",
-            gap: 14
+            gap: 14,
+            overflowY: "auto"
           }}
         >
           <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
@@ -414,10 +1194,34 @@ export default function App() {
             </button>
           </div>
 
+          {settings.autoStart && (
+            <div style={{ display: "flex", flexDirection: "column", gap: 6 }}>
+              <div style={{ fontSize: 13, color: "#8a8a8a" }}>开机启动时</div>
+              <div style={{ display: "flex", gap: 16, flexWrap: "wrap", fontSize: 13, color: "#8a8a8a" }}>
+                {(Object.keys(AUTOSTART_LAUNCH_LABELS) as (keyof AutostartLaunch)[]).map((option) => (
+                  <label key={option} style={{ display: "flex", gap: 6, alignItems: "center" }}>
+                    <input
+                      type="checkbox"
+                      checked={settings.autostartLaunch[option]}
+                      onChange={(event) =>
+                        updateSettings({
+                          autostartLaunch: { ...settings.autostartLaunch, [option]: event.target.checked }
+                        })
+                      }
+                    />
+                    {AUTOSTART_LAUNCH_LABELS[option]}
+                  </label>
+                ))}
+              </div>
+            </div>
+          )}
+
           <div>
             <div style={{ display: "flex", justifyContent: "space-between", marginBottom: 6 }}>
               <div style={{ fontSize: 14, color: "#c7c7c7" }}>滑动速度</div>
-              <div style={{ fontSize: 14 }}>{settings.wordsPerMinute} WPM</div>
+              <div style={{ fontSize: 14 }}>
+                {snapshot?.timed ? "按字幕时间" : `${settings.wordsPerMinute} WPM`}
+              </div>
             </div>
             <input
               type="range"
@@ -467,6 +1271,433 @@ export default function App() {
               style={{ width: "100%" }}
             />
           </div>
+
+          <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+            <div style={{ fontSize: 14, color: "#c7c7c7" }}>显示器</div>
+            <select
+              value={settings.targetMonitor ?? ""}
+              onChange={(event) => pinMonitor(event.target.value || null)}
+              style={{
+                maxWidth: 220,
+                background: "#111",
+                border: "1px solid #2a2a2a",
+                borderRadius: 8,
+                color: "#f5f5f5",
+                padding: "4px 8px"
+              }}
+            >
+              <option value="">自动</option>
+              {settings.targetMonitor &&
+                !monitors.some((monitor) => monitor.key === settings.targetMonitor) && (
+                  <option value={settings.targetMonitor}>未连接的显示器</option>
+                )}
+              {monitors.map((monitor, index) => (
+                <option key={monitor.key} value={monitor.key}>
+                  {monitorLabel(monitor, index)}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+            <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+              <div style={{ fontSize: 14, color: "#c7c7c7" }}>窗口位置</div>
+              <select
+                value={settings.placement.mode}
+                onChange={(event) => {
+                  const mode = event.target.value as Placement["mode"];
+                  applyPlacement(mode === "custom" ? customPlacement : { mode });
+                }}
+                style={{
+                  background: "#111",
+                  border: "1px solid #2a2a2a",
+                  borderRadius: 8,
+                  color: "#f5f5f5",
+                  padding: "4px 8px"
+                }}
+              >
+                {(Object.keys(PLACEMENT_LABELS) as Placement["mode"][]).map((mode) => (
+                  <option key={mode} value={mode}>
+                    {PLACEMENT_LABELS[mode]}
+                  </option>
+                ))}
+              </select>
+            </div>
+            {settings.placement.mode === "custom" && (
+              <div style={{ display: "flex", gap: 8, alignItems: "center", fontSize: 12, color: "#8a8a8a" }}>
+                <select
+                  value={customPlacement.anchor}
+                  onChange={(event) =>
+                    applyPlacement({ ...customPlacement, anchor: event.target.value as Anchor })
+                  }
+                  style={{
+                    background: "#111",
+                    border: "1px solid #2a2a2a",
+                    borderRadius: 8,
+                    color: "#f5f5f5",
+                    padding: "4px 8px"
+                  }}
+                >
+                  {(Object.keys(ANCHOR_LABELS) as Anchor[]).map((anchor) => (
+                    <option key={anchor} value={anchor}>
+                      {ANCHOR_LABELS[anchor]}
+                    </option>
+                  ))}
+                </select>
+                {(["offsetX", "offsetY"] as const).map((axis) => (
+                  <label key={axis} style={{ display: "flex", gap: 4, alignItems: "center" }}>
+                    {axis === "offsetX" ? "X" : "Y"}
+                    <input
+                      type="number"
+                      value={customPlacement[axis]}
+                      onChange={(event) =>
+                        applyPlacement({ ...customPlacement, [axis]: Number(event.target.value) || 0 })
+                      }
+                      style={{
+                        width: 64,
+                        background: "#111",
+                        border: "1px solid #2a2a2a",
+                        borderRadius: 8,
+                        color: "#f5f5f5",
+                        padding: "4px 6px"
+                      }}
+                    />
+                  </label>
+                ))}
+              </div>
+            )}
+          </div>
+
+          <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+            <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+              <div style={{ fontSize: 14, color: "#c7c7c7" }}>输出窗口</div>
+              <div style={{ display: "flex", gap: 8 }}>
+                {[
+                  { label: "打开", command: "open_output_window" },
+                  { label: "关闭", command: "close_output_window" }
+                ].map((action) => (
+                  <button
+                    key={action.command}
+                    onClick={() =>
+                      invoke(action.command).catch(() => {
+                        return;
+                      })
+                    }
+                    style={{
+                      fontSize: 12,
+                      background: "#111",
+                      border: "1px solid #2a2a2a",
+                      borderRadius: 8,
+                      color: "#c7c7c7",
+                      cursor: "pointer",
+                      padding: "6px 8px"
+                    }}
+                  >
+                    {action.label}
+                  </button>
+                ))}
+              </div>
+            </div>
+            <select
+              value={settings.outputMonitor ?? ""}
+              onChange={(event) => chooseOutputMonitor(event.target.value || null)}
+              style={{
+                background: "#111",
+                border: "1px solid #2a2a2a",
+                borderRadius: 8,
+                color: "#f5f5f5",
+                padding: "4px 8px"
+              }}
+            >
+              <option value="">自动（优先副屏）</option>
+              {monitors.map((monitor, index) => (
+                <option key={monitor.key} value={monitor.key}>
+                  {monitorLabel(monitor, index)}
+                </option>
+              ))}
+            </select>
+            <div style={{ display: "flex", gap: 16, fontSize: 13, color: "#8a8a8a" }}>
+              {(["horizontal", "vertical"] as const).map((axis) => (
+                <label key={axis} style={{ display: "flex", gap: 6, alignItems: "center" }}>
+                  <input
+                    type="checkbox"
+                    checked={settings.mirror[axis]}
+                    onChange={(event) =>
+                      updateSettings({ mirror: { ...settings.mirror, [axis]: event.target.checked } })
+                    }
+                  />
+                  {axis === "horizontal" ? "水平镜像" : "垂直翻转"}
+                </label>
+              ))}
+            </div>
+          </div>
+
+          <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+            <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+              <div style={{ fontSize: 14, color: "#c7c7c7" }}>悬浮穿透</div>
+              <input
+                type="checkbox"
+                checked={settings.overlay.enabled}
+                onChange={(event) =>
+                  updateSettings({ overlay: { ...settings.overlay, enabled: event.target.checked } })
+                }
+              />
+            </div>
+            <label style={{ display: "flex", gap: 8, alignItems: "center", fontSize: 13, color: "#8a8a8a" }}>
+              背景不透明度
+              <input
+                type="range"
+                min={0.1}
+                max={1}
+                step={0.05}
+                value={settings.overlay.opacity}
+                onChange={(event) =>
+                  updateSettings({ overlay: { ...settings.overlay, opacity: Number(event.target.value) } })
+                }
+                style={{ flex: 1 }}
+              />
+              {Math.round(settings.overlay.opacity * 100)}%
+            </label>
+            <div style={{ fontSize: 12, color: "#6b6b6b" }}>
+              开启后窗口不接收鼠标点击，用「{HOTKEY_LABELS.toggleOverlay}」快捷键切回。
+            </div>
+          </div>
+
+          <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+            <div style={{ fontSize: 14, color: "#c7c7c7" }}>flashprompter:// 链接允许</div>
+            <div style={{ display: "flex", gap: 16, flexWrap: "wrap", fontSize: 13, color: "#8a8a8a" }}>
+              {(Object.keys(LINK_ACTION_LABELS) as LinkAction[]).map((action) => (
+                <label key={action} style={{ display: "flex", gap: 6, alignItems: "center" }}>
+                  <input
+                    type="checkbox"
+                    checked={settings.linkAllowlist.includes(action)}
+                    onChange={(event) =>
+                      updateSettings({
+                        linkAllowlist: event.target.checked
+                          ? [...settings.linkAllowlist, action]
+                          : settings.linkAllowlist.filter((item) => item !== action)
+                      })
+                    }
+                  />
+                  {LINK_ACTION_LABELS[action]}
+                </label>
+              ))}
+            </div>
+          </div>
+
+          <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+            <div style={{ fontSize: 14, color: "#c7c7c7" }}>防录屏</div>
+            {captures.map((capture) => (
+              <label
+                key={capture.label}
+                style={{
+                  display: "flex",
+                  gap: 6,
+                  alignItems: "center",
+                  fontSize: 13,
+                  color: capture.status.status === "failed" ? "#f87171" : "#8a8a8a"
+                }}
+              >
+                <input
+                  type="checkbox"
+                  checked={capture.enabled}
+                  disabled={capture.status.status === "unsupported"}
+                  onChange={(event) => setCaptureExclusion(capture.label, event.target.checked)}
+                />
+                {captureLabel(capture)}
+              </label>
+            ))}
+          </div>
+
+          <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+            <div style={{ fontSize: 14, color: "#c7c7c7" }}>文字颜色</div>
+            <input
+              type="color"
+              value={settings.textColor}
+              onChange={(event) => updateSettings({ textColor: event.target.value })}
+            />
+          </div>
+
+          <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+            <div style={{ fontSize: 14, color: "#c7c7c7" }}>背景颜色</div>
+            <input
+              type="color"
+              value={settings.backgroundColor}
+              onChange={(event) => updateSettings({ backgroundColor: event.target.value })}
+            />
+          </div>
+
+          <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+            <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+              <div style={{ fontSize: 14, color: "#c7c7c7" }}>配置方案</div>
+              <select
+                value={settings.activeProfile ?? ""}
+                onChange={(event) => {
+                  if (event.target.value) {
+                    runProfileCommand("switch_profile", { name: event.target.value });
+                  }
+                }}
+                style={{
+                  background: "#111",
+                  border: "1px solid #2a2a2a",
+                  borderRadius: 8,
+                  color: "#f5f5f5",
+                  padding: "4px 8px"
+                }}
+              >
+                <option value="">未选择</option>
+                {settings.profiles.map((profile) => (
+                  <option key={profile.name} value={profile.name}>
+                    {profile.name}
+                  </option>
+                ))}
+              </select>
+            </div>
+            <div style={{ display: "flex", gap: 8 }}>
+              <input
+                value={profileName}
+                placeholder={settings.activeProfile ?? "新方案名称"}
+                onChange={(event) => setProfileName(event.target.value)}
+                style={{
+                  flex: 1,
+                  background: "#111",
+                  border: "1px solid #2a2a2a",
+                  borderRadius: 8,
+                  color: "#f5f5f5",
+                  fontSize: 12,
+                  padding: "6px 8px"
+                }}
+              />
+              {[
+                { label: "保存", onClick: saveProfile },
+                {
+                  label: "删除",
+                  onClick: () =>
+                    settings.activeProfile &&
+                    runProfileCommand("delete_profile", { name: settings.activeProfile })
+                },
+                { label: "导入", onClick: () => runProfileCommand("import_profiles") },
+                { label: "导出", onClick: exportProfiles }
+              ].map((action) => (
+                <button
+                  key={action.label}
+                  onClick={action.onClick}
+                  style={{
+                    fontSize: 12,
+                    background: "#111",
+                    border: "1px solid #2a2a2a",
+                    borderRadius: 8,
+                    color: "#c7c7c7",
+                    cursor: "pointer",
+                    padding: "6px 8px"
+                  }}
+                >
+                  {action.label}
+                </button>
+              ))}
+            </div>
+            <div style={{ fontSize: 12, color: "#8a8a8a" }}>
+              保存时会记录当前速度、字号、行高、颜色和窗口位置
+            </div>
+            {profileError && <div style={{ fontSize: 12, color: "#f87171" }}>{profileError}</div>}
+          </div>
+
+          <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+            <div style={{ fontSize: 14, color: "#c7c7c7" }}>手机遥控</div>
+            <button
+              aria-label={phoneRemoteEnabled ? "关闭手机遥控" : "开启手机遥控"}
+              onClick={togglePhoneRemote}
+              style={{
+                width: 52,
+                height: 28,
+                borderRadius: 999,
+                border: "1px solid #2a2a2a",
+                background: phoneRemoteEnabled ? "#2563eb" : "#111",
+                cursor: "pointer",
+                padding: 2,
+                display: "flex",
+                alignItems: "center",
+                justifyContent: phoneRemoteEnabled ? "flex-end" : "flex-start"
+              }}
+            >
+              <span
+                style={{
+                  width: 22,
+                  height: 22,
+                  borderRadius: "50%",
+                  background: "#f5f5f5",
+                  display: "block"
+                }}
+              />
+            </button>
+          </div>
+
+          {pairing && (
+            <div style={{ display: "flex", flexDirection: "column", alignItems: "center", gap: 8 }}>
+              <div
+                dangerouslySetInnerHTML={{ __html: pairing.qrSvg }}
+                style={{ background: "#fff", padding: 8, borderRadius: 8, lineHeight: 0 }}
+              />
+              <div style={{ fontSize: 13, color: "#c7c7c7" }}>
+                手机扫码，或打开 {pairing.url.split("?")[0]} 输入配对码
+              </div>
+              <div style={{ fontSize: 22, letterSpacing: 6 }}>{pairing.code}</div>
+            </div>
+          )}
+
+          <div style={{ display: "flex", flexDirection: "column", gap: 8 }}>
+            <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center" }}>
+              <div style={{ fontSize: 14, color: "#c7c7c7" }}>全局快捷键</div>
+              <button
+                onClick={resetHotkeys}
+                style={{
+                  fontSize: 12,
+                  color: "#c7c7c7",
+                  background: "transparent",
+                  border: "none",
+                  cursor: "pointer"
+                }}
+              >
+                恢复默认
+              </button>
+            </div>
+            {hotkeys.map((binding) => (
+              <div
+                key={binding.action}
+                style={{ display: "flex", justifyContent: "space-between", alignItems: "center", gap: 8 }}
+              >
+                <div style={{ fontSize: 13, color: binding.error ? "#f87171" : "#8a8a8a" }}>
+                  {HOTKEY_LABELS[binding.action]}
+                </div>
+                <input
+                  readOnly
+                  value={binding.accelerator}
+                  placeholder="未设置"
+                  title={binding.error ?? undefined}
+                  onKeyDown={(event) => {
+                    event.preventDefault();
+                    if (event.key === "Backspace" || event.key === "Delete") {
+                      bindHotkey(binding.action, "");
+                      return;
+                    }
+                    const accelerator = acceleratorFromEvent(event);
+                    if (accelerator) bindHotkey(binding.action, accelerator);
+                  }}
+                  style={{
+                    width: 200,
+                    background: "#111",
+                    border: `1px solid ${binding.error ? "#f87171" : "#2a2a2a"}`,
+                    borderRadius: 8,
+                    color: "#f5f5f5",
+                    fontSize: 12,
+                    padding: "6px 8px",
+                    textAlign: "center"
+                  }}
+                />
+              </div>
+            ))}
+            {hotkeyError && <div style={{ fontSize: 12, color: "#f87171" }}>{hotkeyError}</div>}
+          </div>
         </div>
 
       </div>
@@ -489,7 +1720,8 @@ export default function App() {
         <div
           style={{
             flex: 1,
-            background: "#0f0f0f",
+            background: settings.backgroundColor,
+            color: settings.textColor,
             borderRadius: 16,
             border: "1px solid #1a1a1a",
             padding: 12,
@@ -507,9 +1739,11 @@ export default function App() {
               whiteSpace: "pre-wrap"
             }}
           >
-            {content}
+            {renderScript()}
           </div>
         </div>
+        {renderCaptureWarning()}
+        {renderTimeline()}
         {renderControls(mode)}
       </div>
     );
@@ -527,13 +1761,11 @@ export default function App() {
         background: "#0b0b0b"
       }}
     >
+      {renderLibrary()}
       <textarea
         value={content}
         onChange={(event) => {
           setContent(event.target.value);
-          setProgress(0);
-          startProgressRef.current = 0;
-          setIsPlaying(false);
         }}
         style={{
           flex: 1,
@@ -547,6 +1779,25 @@ export default function App() {
           resize: "none"
         }}
       />
+      {parsed && parsed.errors.length > 0 && (
+        <div style={{ fontSize: 13, color: "#f87171", display: "flex", flexDirection: "column", gap: 4 }}>
+          {parsed.errors.map((error, index) => (
+            <div key={index}>
+              第 {error.line} 行第 {error.column} 列：{error.message}
+            </div>
+          ))}
+        </div>
+      )}
+      {linkNotice && (
+        <div
+          onClick={() => setLinkNotice(null)}
+          style={{ fontSize: 13, color: "#fbbf24", cursor: "pointer" }}
+        >
+          {linkNotice}
+        </div>
+      )}
+      {renderCaptureWarning()}
+      {renderTimeline()}
       {renderControls(mode)}
     </div>
   );